@@ -2,8 +2,11 @@
 
 use gloo_console::log;
 use gloo_net::http::{Method, Request};
-use gloo_storage::{LocalStorage, Storage};
-use seed::{prelude::*, *};
+use seed::{
+    prelude::*,
+    storage::{LocalStorage, Storage},
+    *,
+};
 use serde::{Deserialize, Serialize};
 
 const LOGIN: &str = "login";
@@ -149,7 +152,7 @@ fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
             log!(format!("{error}"));
         }
         Msg::LogoutClicked => {
-            LocalStorage::delete(STORAGE_KEY);
+            LocalStorage::remove(STORAGE_KEY).expect("remove user");
             model.user = None;
             model.secret_message = None;
         }
@@ -119,5 +119,5 @@ fn header(base_url: &Url) -> Node<Msg> {
 
 #[wasm_bindgen(start)]
 pub fn start() {
-    App::start("app", init, update, view);
+    App::start("app", init, update, view).routing_mode(RoutingMode::Hash);
 }
@@ -1,9 +1,12 @@
 #![allow(clippy::use_self)]
 
 use enclose::enc;
-use gloo_storage::{LocalStorage, Storage};
 use indexmap::IndexMap;
-use seed::{prelude::*, *};
+use seed::{
+    prelude::*,
+    storage::{LocalStorage, Storage},
+    *,
+};
 use serde::{Deserialize, Serialize};
 use std::mem;
 use uuid::Uuid;
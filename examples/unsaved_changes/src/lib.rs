@@ -1,4 +1,3 @@
-use gloo_console::log;
 use gloo_storage::{LocalStorage, Storage};
 use seed::{prelude::*, *};
 use std::{
@@ -14,16 +13,15 @@ const STORAGE_KEY: &str = "seed_unsaved_changes_text";
 
 #[allow(clippy::needless_pass_by_value)]
 fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
-    // https://developer.mozilla.org/en-US/docs/Web/API/WindowEventHandlers/onbeforeunload
-    orders
-        .stream(streams::window_event(Ev::BeforeUnload, Msg::BeforeUnload))
-        .subscribe(Msg::UrlRequested);
+    // Guards link clicks, back / forward navigation and page unloading.
+    orders.guard_navigation(Msg::NavigationRequested);
 
     let text = LocalStorage::get(STORAGE_KEY).unwrap_or_default();
     Model {
         base_url: url.to_base_url(),
         saved_text_hash: calculate_hash(&text),
         text,
+        pending_navigation: None,
     }
 }
 
@@ -35,6 +33,14 @@ struct Model {
     base_url: Url,
     saved_text_hash: u64,
     text: String,
+    // The navigation waiting for the user's confirmation.
+    pending_navigation: Option<subs::Navigation>,
+}
+
+impl Model {
+    fn has_unsaved_changes(&self) -> bool {
+        calculate_hash(&self.text) != self.saved_text_hash
+    }
 }
 
 // ------ ------
@@ -58,8 +64,9 @@ impl<'a> Urls<'a> {
 enum Msg {
     TextChanged(String),
     Save,
-    UrlRequested(subs::UrlRequested),
-    BeforeUnload(web_sys::Event),
+    NavigationRequested(subs::Navigation),
+    Leave,
+    Stay,
 }
 
 fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
@@ -69,24 +76,25 @@ fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
             LocalStorage::set(STORAGE_KEY, &model.text).expect("save text");
             model.saved_text_hash = calculate_hash(&model.text);
         }
-        Msg::UrlRequested(subs::UrlRequested(_, url_request)) => {
-            if calculate_hash(&model.text) == model.saved_text_hash {
-                return;
+        Msg::NavigationRequested(navigation) => {
+            if !model.has_unsaved_changes() {
+                return navigation.allow();
             }
-            if Ok(true)
-                == window().confirm_with_message("Do you want to leave? Data won't be saved.")
-            {
-                return;
+            // The browser shows its own confirmation dialog before unloading the page.
+            if navigation.kind() == subs::NavigationKind::Unload {
+                return navigation.block();
             }
-            url_request.handled_and_prevent_refresh();
+            navigation.defer();
+            model.pending_navigation = Some(navigation);
         }
-        Msg::BeforeUnload(event) => {
-            if calculate_hash(&model.text) != model.saved_text_hash {
-                log!("attempt to prevent navigation");
-                let event = event.unchecked_into::<web_sys::BeforeUnloadEvent>();
-                event.prevent_default();
-                // Because of Chrome
-                event.set_return_value("");
+        Msg::Leave => {
+            if let Some(navigation) = model.pending_navigation.take() {
+                navigation.allow();
+            }
+        }
+        Msg::Stay => {
+            if let Some(navigation) = model.pending_navigation.take() {
+                navigation.block();
             }
         }
     }
@@ -111,7 +119,7 @@ fn view(model: &Model) -> Node<Msg> {
         ],
         div![
             style! {St::Display => "flex", St::JustifyContent => "space-between", St::Padding => px(10)},
-            span![IF!(calculate_hash(&model.text) != model.saved_text_hash => "Unsaved changes")],
+            span![IF!(model.has_unsaved_changes() => "Unsaved changes")],
             button![ev(Ev::Click, |_| Msg::Save), "Save"],
         ],
         div![
@@ -125,6 +133,25 @@ fn view(model: &Model) -> Node<Msg> {
                 "/no-home"
             ],
             a![attrs! {At::Href => "https://example.com"}, "example.com"],
+        ],
+        model
+            .pending_navigation
+            .as_ref()
+            .map(view_leave_confirmation),
+    ]
+}
+
+fn view_leave_confirmation(navigation: &subs::Navigation) -> Node<Msg> {
+    div![
+        style! {St::Display => "flex", St::FlexDirection => "column", St::Padding => px(10), St::MarginTop => px(20), St::Border => "1px solid gray"},
+        format!(
+            "Do you want to leave to \"{}\"? Data won't be saved.",
+            navigation.url()
+        ),
+        div![
+            style! {St::Display => "flex", St::JustifyContent => "space-between", St::PaddingTop => px(10)},
+            button![ev(Ev::Click, |_| Msg::Leave), "Leave"],
+            button![ev(Ev::Click, |_| Msg::Stay), "Stay"],
         ]
     ]
 }
@@ -14,7 +14,7 @@ fn init(_: Url, _: &mut impl Orders<Msg>) -> Model {
 
 #[derive(Default)]
 struct Model {
-    event_streams: Vec<StreamHandle>,
+    watching: bool,
     point: Point,
     key_code: u32,
 }
@@ -35,22 +35,9 @@ enum Msg {
     KeyPressed(web_sys::KeyboardEvent),
 }
 
-fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
     match msg {
-        Msg::ToggleWatching => {
-            if model.event_streams.is_empty() {
-                model.event_streams = vec![
-                    orders.stream_with_handle(streams::window_event(Ev::MouseMove, |event| {
-                        Msg::MouseMoved(event.unchecked_into())
-                    })),
-                    orders.stream_with_handle(streams::window_event(Ev::KeyDown, |event| {
-                        Msg::KeyPressed(event.unchecked_into())
-                    })),
-                ];
-            } else {
-                model.event_streams.clear();
-            }
-        }
+        Msg::ToggleWatching => model.watching = !model.watching,
         Msg::MouseMoved(ev) => {
             model.point = Point {
                 x: ev.client_x(),
@@ -71,20 +58,34 @@ fn view(model: &Model) -> Vec<Node<Msg>> {
         h2![format!("Last key pressed: {}", model.key_code)],
         button![
             ev(Ev::Click, |_| Msg::ToggleWatching),
-            if model.event_streams.is_empty() {
-                "Start watching"
-            } else {
+            if model.watching {
                 "Stop watching"
+            } else {
+                "Start watching"
             }
         ],
     ]
 }
 
+// ------ ------
+//  Window Events
+// ------ ------
+
+fn window_events(model: &Model) -> Vec<EventHandler<Msg>> {
+    if !model.watching {
+        return Vec::new();
+    }
+    vec![
+        mouse_ev(Ev::MouseMove, Msg::MouseMoved),
+        keyboard_ev(Ev::KeyDown, Msg::KeyPressed),
+    ]
+}
+
 // ------ ------
 //     Start
 // ------ ------
 
 #[wasm_bindgen(start)]
 pub fn start() {
-    App::start("app", init, update, view);
+    App::start("app", init, update, view).window_events(window_events);
 }
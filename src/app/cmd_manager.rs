@@ -1,15 +1,32 @@
 use futures::future::{abortable, AbortHandle, Future, FutureExt};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use uuid::Uuid;
 use wasm_bindgen_futures::spawn_local;
 
 // ------ CmdManager ------
 
-pub(crate) struct CmdManager;
+#[derive(Default)]
+pub(crate) struct CmdManager {
+    // Handles of the running cmds performed without a handle.
+    // They are aborted when the `App` is unmounted.
+    handles: Rc<RefCell<HashMap<Uuid, CmdHandle>>>,
+    pub keyed_cmds: KeyedCmds,
+}
 
 impl CmdManager {
-    pub fn perform_cmd(cmd: impl Future<Output = ()> + 'static) {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn perform_cmd(&self, cmd: impl Future<Output = ()> + 'static) {
+        let id = Uuid::new_v4();
+        let handles = Rc::clone(&self.handles);
         // The future is "leaked" into the JS world as a promise.
         // It's always executed on the next JS tick to prevent stack overflow.
-        spawn_local(cmd);
+        let handle = Self::perform_cmd_with_handle(cmd.map(move |()| {
+            handles.borrow_mut().remove(&id);
+        }));
+        self.handles.borrow_mut().insert(id, handle);
     }
 
     pub fn perform_cmd_with_handle(cmd: impl Future<Output = ()> + 'static) -> CmdHandle {
@@ -18,12 +35,75 @@ impl CmdManager {
         spawn_local(cmd.map(move |_| ()));
         CmdHandle(handle)
     }
+
+    /// Abort all running cmds performed without a handle (including keyed ones).
+    pub fn abort_all(&self) {
+        let handles = std::mem::take(&mut *self.handles.borrow_mut());
+        drop(handles);
+        self.keyed_cmds.abort_all();
+    }
+}
+
+// ------ KeyedCmds ------
+
+/// Handles of pending keyed cmds - see `Orders::perform_cmd_keyed`.
+#[derive(Default)]
+pub(crate) struct KeyedCmds {
+    handles: Rc<RefCell<HashMap<String, (Uuid, CmdHandle)>>>,
+}
+
+impl KeyedCmds {
+    /// Register the `cmd` under the `key` and abort the pending cmd with the same `key`.
+    ///
+    /// The returned future resolves to `None` when the cmd is aborted.
+    pub fn register<T: 'static>(
+        &self,
+        key: String,
+        cmd: impl Future<Output = T> + 'static,
+    ) -> impl Future<Output = Option<T>> {
+        let id = Uuid::new_v4();
+        let (cmd, handle) = abortable(cmd);
+
+        let old_handle = self
+            .handles
+            .borrow_mut()
+            .insert(key.clone(), (id, CmdHandle(handle)));
+        // Dropped after the `borrow_mut` ends to abort the replaced cmd.
+        drop(old_handle);
+
+        let handles = Rc::clone(&self.handles);
+        cmd.map(move |result| {
+            let mut handles = handles.borrow_mut();
+            // The entry may already belong to a newer cmd with the same key.
+            if handles
+                .get(&key)
+                .map_or(false, |(current_id, _)| *current_id == id)
+            {
+                handles.remove(&key);
+            }
+            result.ok()
+        })
+    }
+
+    pub fn cancel(&self, key: &str) {
+        let handle = self.handles.borrow_mut().remove(key);
+        drop(handle);
+    }
+
+    pub fn is_pending(&self, key: &str) -> bool {
+        self.handles.borrow().contains_key(key)
+    }
+
+    pub fn abort_all(&self) {
+        let handles = std::mem::take(&mut *self.handles.borrow_mut());
+        drop(handles);
+    }
 }
 
 // ------ CmdHandle ------
 
 #[derive(Debug)]
-pub struct CmdHandle(AbortHandle);
+pub struct CmdHandle(pub(crate) AbortHandle);
 
 impl Drop for CmdHandle {
     fn drop(&mut self) {
@@ -1,8 +1,7 @@
+use super::{retry_policy::Retries, RetryPolicy};
 use futures::future::{Future, FutureExt};
 use gloo_timers::future::TimeoutFuture;
 
-// @TODO add fetch cmd?
-
 // ------ Timeout cmd ------
 
 /// Set timeout in milliseconds.
@@ -15,14 +14,72 @@ use gloo_timers::future::TimeoutFuture;
 ///orders.perform_cmd_with_handle(cmds::timeout(2000, || Msg::OnTimeout));
 ///orders.perform_cmd(cmds::timeout(1000, || log!("Tick!")));
 /// ```
-///
-/// # Panics
-///
-/// Panics when the command doesn't return `Msg`, `Option<Msg>` or `()`.
-/// (It will be changed to a compile-time error).
 pub fn timeout<MsU>(
     ms: u32,
     handler: impl FnOnce() -> MsU + Clone + 'static,
 ) -> impl Future<Output = MsU> {
     TimeoutFuture::new(ms).map(move |_| handler())
 }
+
+// ------ Retry cmd ------
+
+/// Run the future created by `make_future` until it succeeds or the `policy` gives up.
+/// Resolves to the first `Ok` or to the last `Err`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///let policy = RetryPolicy::exponential(500).jitter(Jitter::Full).max_attempts(4);
+///orders.perform_cmd(async move {
+///    Msg::UserFetched(cmds::retry(policy, move || fetch_user(id)).await)
+///});
+/// ```
+pub async fn retry<T, E, Fut>(
+    policy: RetryPolicy,
+    mut make_future: impl FnMut() -> Fut,
+) -> Result<T, E>
+where
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut retries = Retries::new(policy);
+    loop {
+        let error = match make_future().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        match retries.next_delay() {
+            Some(delay) => TimeoutFuture::new(delay).await,
+            None => return Err(error),
+        }
+    }
+}
+
+// ------ Broadcast cmd ------
+
+/// Send the `message` to the `streams::broadcast_channel`s with the same channel `name`
+/// in other tabs (and other browsing contexts of the same origin).
+///
+/// The `message` is serialized immediately; failures are logged to the console.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.perform_cmd(cmds::broadcast("auth", &AuthMessage::LoggedOut));
+/// ```
+///
+/// # Panics
+///
+/// Panics when the browser doesn't support `BroadcastChannel`.
+#[cfg(feature = "serde")]
+pub fn broadcast<T: serde::Serialize + ?Sized>(
+    name: &str,
+    message: &T,
+) -> impl Future<Output = ()> {
+    let channel = super::streams::broadcast_channel_stream::channel(name);
+    let message = crate::browser::json::to_js_value(message);
+    async move {
+        if let Err(error) = message.and_then(|message| channel.post_message(&message)) {
+            web_sys::console::error_2(&"Cannot broadcast message:".into(), &error);
+        }
+    }
+}
@@ -1,10 +1,21 @@
-use super::{RenderInfo, SubManager};
+use super::middleware::Middleware;
+use super::panic_handler::PanicHandlerGuard;
+#[cfg(feature = "serde")]
+use super::persistence::Persister;
+#[cfg(feature = "time-travel")]
+use super::time_travel::Recorder;
+use super::{CmdManager, RenderInfo, StreamManager, SubManager};
+#[cfg(feature = "routing")]
+use crate::browser::service::routing::{RoutingMode, ScrollRestoration};
 use crate::browser::util;
-use crate::virtual_dom::{El, EventHandlerManager};
+use crate::virtual_dom::{El, EventHandler, EventHandlerManager};
 use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
 
 type StoredPopstate = RefCell<Option<Closure<dyn FnMut(web_sys::Event)>>>;
+type WindowEvents<Ms, Mdl> = Rc<dyn Fn(&Mdl) -> Vec<EventHandler<Ms>>>;
+type StoredLinkListener = RefCell<Option<Closure<dyn Fn(web_sys::Event)>>>;
 
 #[allow(clippy::type_complexity, dead_code)]
 pub(crate) struct AppData<Ms: 'static, Mdl> {
@@ -12,10 +23,36 @@ pub(crate) struct AppData<Ms: 'static, Mdl> {
     pub(crate) root_el: RefCell<Option<El<Ms>>>,
     pub popstate_closure: StoredPopstate,
     pub hashchange_closure: StoredPopstate,
+    pub link_listener_closure: StoredLinkListener,
+    // Set by `Orders::guard_navigation`.
+    #[cfg(feature = "routing")]
+    pub(crate) beforeunload_closure: StoredPopstate,
+    // Set by `App::routing_mode`.
+    #[cfg(feature = "routing")]
+    pub(crate) routing_mode: Cell<RoutingMode>,
+    // Set by `App::scroll_restoration`.
+    #[cfg(feature = "routing")]
+    pub(crate) scroll_restoration: Cell<ScrollRestoration>,
+    #[cfg(feature = "routing")]
+    pub(crate) scroll_closure: StoredPopstate,
+    // Set by `App::window_events`.
+    pub(crate) window_events: RefCell<Option<WindowEvents<Ms, Mdl>>>,
     pub window_event_handler_manager: RefCell<EventHandlerManager<Ms>>,
     pub sub_manager: RefCell<SubManager<Ms>>,
-    pub msg_listeners: RefCell<Vec<Box<dyn Fn(&Ms)>>>,
+    pub(crate) cmd_manager: RefCell<CmdManager>,
+    pub(crate) stream_manager: RefCell<StreamManager>,
+    pub(crate) middlewares: RefCell<Vec<Middleware<Ms, Mdl>>>,
     pub scheduled_render_handle: RefCell<Option<util::RequestAnimationFrameHandle>>,
     pub after_next_render_callbacks: RefCell<Vec<Box<dyn FnOnce(RenderInfo) -> Option<Ms>>>>,
     pub render_info: Cell<Option<RenderInfo>>,
+    pub(crate) unmounted: Cell<bool>,
+    // Set by `App::on_panic`.
+    pub(crate) last_message_recorder: RefCell<Option<Box<dyn Fn(&Ms)>>>,
+    pub(crate) panic_handler_guard: RefCell<Option<PanicHandlerGuard>>,
+    // Set by `App::enable_time_travel`.
+    #[cfg(feature = "time-travel")]
+    pub(crate) update_recorder: RefCell<Option<Rc<dyn Recorder<Ms, Mdl>>>>,
+    // Set by `App::start_persisted`.
+    #[cfg(feature = "serde")]
+    pub(crate) persister: RefCell<Option<Rc<Persister<Mdl>>>>,
 }
@@ -0,0 +1,75 @@
+/// Output of cmds, streams and handlers - `Ms`, `Option<Ms>` or `()`.
+///
+/// Any other output is a compile-time error.
+///
+/// `Marker` only distinguishes the implementations (otherwise `()` as `Ms` would conflict
+/// with `()` as "no message") and it's always inferred - just add it as a generic parameter
+/// when you forward handlers in your own functions:
+///
+/// ```rust,ignore
+/// fn on_click<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+///     handler: impl FnOnce() -> MsU + Clone + 'static,
+/// ) -> EventHandler<Ms> {
+///     ev(Ev::Click, move |_| handler())
+/// }
+/// ```
+///
+/// _Note:_ When `Ms` is `()`, the output `()` matches both `Ms` and "no message", so the `Marker`
+/// can't be inferred. Return `Some(())` or `None` from handlers in such apps instead.
+pub trait IntoOptionMsg<Ms, Marker> {
+    fn into_option_msg(self) -> Option<Ms>;
+}
+
+/// `Marker` for `Ms`.
+pub enum MsgMarker {}
+
+/// `Marker` for `Option<Ms>`.
+pub enum OptionMsgMarker {}
+
+/// `Marker` for `()`.
+pub enum UnitMarker {}
+
+impl<Ms> IntoOptionMsg<Ms, MsgMarker> for Ms {
+    fn into_option_msg(self) -> Option<Ms> {
+        Some(self)
+    }
+}
+
+impl<Ms> IntoOptionMsg<Ms, OptionMsgMarker> for Option<Ms> {
+    fn into_option_msg(self) -> Option<Ms> {
+        self
+    }
+}
+
+impl<Ms> IntoOptionMsg<Ms, UnitMarker> for () {
+    fn into_option_msg(self) -> Option<Ms> {
+        None
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert<Ms, MsU: IntoOptionMsg<Ms, Marker>, Marker>(output: MsU) -> Option<Ms> {
+        output.into_option_msg()
+    }
+
+    #[test]
+    fn convert_valid_outputs() {
+        assert_eq!(convert::<u8, _, _>(1_u8), Some(1));
+        assert_eq!(convert::<u8, _, _>(Some(2_u8)), Some(2));
+        assert_eq!(convert::<u8, _, _>(None::<u8>), None);
+        assert_eq!(convert::<u8, _, _>(()), None);
+    }
+
+    #[test]
+    fn convert_unit_messages() {
+        assert_eq!(convert::<(), _, _>(Some(())), Some(()));
+        assert_eq!(convert::<(), _, _>(None::<()>), None);
+        assert_eq!(convert::<(), _, MsgMarker>(()), Some(()));
+        assert_eq!(convert::<(), _, UnitMarker>(()), None);
+    }
+}
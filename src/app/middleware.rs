@@ -0,0 +1,32 @@
+use std::rc::Rc;
+
+// ------ MiddlewareAction ------
+
+/// What should happen with the message after a middleware has processed it.
+///
+/// See `App::add_middleware`.
+#[derive(Debug)]
+pub enum MiddlewareAction<Ms> {
+    /// Pass the message to the next middleware or to `update`.
+    Continue,
+    /// Pass the given message instead of the original one.
+    Replace(Ms),
+    /// Drop the message - the next middlewares and `update` won't be invoked.
+    Drop,
+    /// Pass the message to the next middleware after the given number of milliseconds.
+    Delay(u32),
+}
+
+// ------ MiddlewareContext ------
+
+/// Data available to middlewares besides the message.
+#[derive(Debug)]
+pub struct MiddlewareContext<'a, Mdl> {
+    /// The current model - i.e. the model before the message is passed to `update`.
+    pub model: &'a Mdl,
+}
+
+// ------ Middleware ------
+
+pub(crate) type Middleware<Ms, Mdl> =
+    Rc<dyn Fn(&Ms, MiddlewareContext<Mdl>) -> MiddlewareAction<Ms>>;
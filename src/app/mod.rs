@@ -1,14 +1,16 @@
 #![allow(clippy::module_name_repetitions)]
 
-use crate::browser::dom::virtual_dom_bridge;
 #[cfg(feature = "routing")]
 use crate::browser::service::routing;
 use crate::browser::{
     util::{self, window},
     Url, DUMMY_BASE_URL,
 };
-use crate::virtual_dom::{patch, El, EventHandlerManager, IntoNodes, Mailbox, Node, Tag};
+use crate::virtual_dom::{patch, El, EventHandler, EventHandlerManager, IntoNodes, Mailbox, Tag};
+use cmd_manager::CmdManager;
 use enclose::enclose;
+use futures::future::FutureExt;
+use gloo_timers::future::TimeoutFuture;
 use std::{
     any::Any,
     cell::{Cell, RefCell},
@@ -16,6 +18,7 @@ use std::{
     fmt,
     rc::Rc,
 };
+use stream_manager::StreamManager;
 use sub_manager::SubManager;
 use wasm_bindgen::closure::Closure;
 
@@ -25,27 +28,46 @@ pub mod cmds;
 pub mod data;
 mod effect;
 pub mod get_element;
+pub mod into_option_msg;
 pub mod message_mapper;
+pub mod middleware;
 pub mod orders;
+pub mod panic_handler;
+#[cfg(feature = "serde")]
+pub mod persistence;
 pub mod render_info;
+pub mod retry_policy;
 pub mod stream_manager;
 pub mod streams;
 pub mod sub_manager;
 #[cfg(feature = "routing")]
 pub mod subs;
+#[cfg(feature = "time-travel")]
+pub mod time_travel;
 
 pub use cfg::AppCfg;
 pub use cmd_manager::CmdHandle;
 pub(crate) use data::AppData;
-use effect::Effect;
+pub(crate) use effect::Effect;
 pub use get_element::GetElement;
+pub use into_option_msg::IntoOptionMsg;
 pub use message_mapper::MessageMapper;
+pub use middleware::{MiddlewareAction, MiddlewareContext};
 pub use orders::{Orders, OrdersContainer, OrdersProxy};
+pub use panic_handler::PanicContext;
+#[cfg(feature = "serde")]
+pub use persistence::Persistence;
 pub use render_info::RenderInfo;
+pub use retry_policy::{Backoff, Jitter, Retries, RetryPolicy};
+#[cfg(feature = "routing")]
+pub use routing::{RoutingMode, ScrollRestoration};
 pub use stream_manager::StreamHandle;
 pub use sub_manager::{Notification, SubHandle};
+#[cfg(feature = "time-travel")]
+pub use time_travel::TimeTravel;
 
 /// Determines if an update should cause the `VDom` to rerender or not.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ShouldRender {
     Render,
     ForceRenderNow,
@@ -95,7 +117,9 @@ where
 {
     /// Create, mount and start the `App`. It's the standard way to create a Seed app.
     ///
-    /// _NOTE:_ It tries to hydrate the root element content => you can use it also for prerendered website.
+    /// _NOTE:_ It hydrates the root element content - existing (e.g. prerendered) DOM nodes are reused
+    /// by the first render and only the differences are patched. Mismatches are reported
+    /// to the console in debug builds.
     ///
     /// # Example
     ///
@@ -178,16 +202,36 @@ where
                 root_el: RefCell::new(None),
                 popstate_closure: RefCell::new(None),
                 hashchange_closure: RefCell::new(None),
+                link_listener_closure: RefCell::new(None),
+                #[cfg(feature = "routing")]
+                beforeunload_closure: RefCell::new(None),
+                #[cfg(feature = "routing")]
+                routing_mode: Cell::new(RoutingMode::default()),
+                #[cfg(feature = "routing")]
+                scroll_restoration: Cell::new(ScrollRestoration::default()),
+                #[cfg(feature = "routing")]
+                scroll_closure: RefCell::new(None),
+                window_events: RefCell::new(None),
                 window_event_handler_manager: RefCell::new(EventHandlerManager::new()),
                 sub_manager: RefCell::new(SubManager::new()),
-                msg_listeners: RefCell::new(Vec::new()),
+                cmd_manager: RefCell::new(CmdManager::new()),
+                stream_manager: RefCell::new(StreamManager::new()),
+                middlewares: RefCell::new(Vec::new()),
                 scheduled_render_handle: RefCell::new(None),
                 after_next_render_callbacks: RefCell::new(Vec::new()),
                 render_info: Cell::new(None),
+                unmounted: Cell::new(false),
+                last_message_recorder: RefCell::new(None),
+                panic_handler_guard: RefCell::new(None),
+                #[cfg(feature = "time-travel")]
+                update_recorder: RefCell::new(None),
+                #[cfg(feature = "serde")]
+                persister: RefCell::new(None),
             }),
         };
 
-        app.data.root_el.replace(Some(app.bootstrap_vdom()));
+        #[cfg(debug_assertions)]
+        El::<Ms>::from(&app.cfg.mount_point).warn_about_script_tags();
 
         let mut orders = OrdersContainer::new(app.clone());
 
@@ -203,26 +247,293 @@ where
         app
     }
 
+    /// Invoke `init` and `view` and render the result to an HTML string.
+    /// It doesn't need a browser - i.e. it can be used also on native targets for prerendering.
+    ///
+    /// See [`seed::ssr`](../ssr/index.html) for more info.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let html = App::render_to_string(Url::new().set_path(["about"]), init, view);
+    /// ```
+    pub fn render_to_string(
+        url: Url,
+        init: impl FnOnce(Url, &mut OrdersContainer<Ms, Mdl, INodes>) -> Mdl,
+        view: impl FnOnce(&Mdl) -> INodes,
+    ) -> String {
+        crate::ssr::render_to_string(url, init, view)
+    }
+
+    /// Select how the app reads and writes the URL - see `RoutingMode`.
+    /// The default mode is `RoutingMode::History`.
+    ///
+    /// In `RoutingMode::Hash` the app listens to `hashchange` events instead of `popstate`,
+    /// and intercepted links and `Orders::request_url` update `location.hash`
+    /// instead of calling `push_state`. `subs::UrlChanged` is notified in both modes.
+    ///
+    /// Call it right after `App::start` - the URL passed to `init` is the same in both modes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::start("app", init, update, view).routing_mode(RoutingMode::Hash);
+    /// ```
+    #[cfg(feature = "routing")]
+    pub fn routing_mode(&self, mode: RoutingMode) {
+        if self.data.unmounted.get() {
+            return;
+        }
+        self.teardown_routing();
+        self.data.routing_mode.set(mode);
+        self.setup_routing_listeners();
+    }
+
+    /// Let Seed manage the scroll position on navigation - see `ScrollRestoration`.
+    /// The default is `ScrollRestoration::Manual`.
+    ///
+    /// In `ScrollRestoration::Automatic` the scroll position of the current route is saved
+    /// to `history.state` before navigating and while the user scrolls.
+    /// Back / forward navigation restores the saved position after the next render - i.e. once
+    /// the `view` of the restored route has been rendered. New routes are scrolled to the top,
+    /// or to the element with the id from the url hash (`RoutingMode::History` only).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::start("app", init, update, view).scroll_restoration(ScrollRestoration::Automatic);
+    /// ```
+    #[cfg(feature = "routing")]
+    pub fn scroll_restoration(&self, scroll_restoration: ScrollRestoration) {
+        use enclose::enc;
+        if self.data.unmounted.get() {
+            return;
+        }
+        self.data.scroll_restoration.set(scroll_restoration);
+        match scroll_restoration {
+            ScrollRestoration::Automatic if self.data.scroll_closure.borrow().is_none() => {
+                routing::setup_scroll_listener(enc!((self => s) move |closure| {
+                    s.data.scroll_closure.replace(Some(closure));
+                }));
+            }
+            ScrollRestoration::Automatic => (),
+            ScrollRestoration::Manual => {
+                if let Some(closure) = self.data.scroll_closure.take() {
+                    routing::remove_scroll_listener(&closure);
+                }
+            }
+        }
+    }
+
     #[cfg(feature = "routing")]
     fn setup_routing(&self, orders: &mut impl Orders<Ms>) {
         use enclose::enc;
-        routing::setup_popstate_listener(
-            enc!((self => s) move |closure| {
-                s.data.popstate_closure.replace(Some(closure));
-            }),
-            enc!((self => s) move |notification| s.notify_with_notification(notification)),
-            Rc::clone(&self.cfg.base_path),
-        );
-        routing::setup_link_listener(
-            enc!((self => s) move |notification| s.notify_with_notification(notification)),
-        );
+        self.setup_routing_listeners();
         orders.subscribe(enc!((self => s) move |url_requested| {
             routing::url_request_handler(
                 url_requested,
                 Rc::clone(&s.cfg.base_path),
+                s.data.routing_mode.get(),
                 move |notification| s.notify_with_notification(notification),
             );
         }));
+        orders.subscribe(enc!((self => s) move |routing::GuardBeforeunload| {
+            s.setup_beforeunload_guard();
+        }));
+        orders.subscribe(enc!((self => s) move |routing::ScrollAfterRender(target)| {
+            if s.data.scroll_restoration.get() == ScrollRestoration::Automatic {
+                s.data
+                    .after_next_render_callbacks
+                    .borrow_mut()
+                    .push(Box::new(move |_| {
+                        routing::scroll_to(&target);
+                        None
+                    }));
+                s.schedule_render();
+            }
+        }));
+    }
+
+    #[cfg(feature = "routing")]
+    fn setup_routing_listeners(&self) {
+        use enclose::enc;
+        let mode = self.data.routing_mode.get();
+        match mode {
+            RoutingMode::History => routing::setup_popstate_listener(
+                enc!((self => s) move |closure| {
+                    s.data.popstate_closure.replace(Some(closure));
+                }),
+                enc!((self => s) move |notification| s.notify_with_notification(notification)),
+                Rc::clone(&self.cfg.base_path),
+            ),
+            RoutingMode::Hash => routing::setup_hashchange_listener(
+                enc!((self => s) move |closure| {
+                    s.data.hashchange_closure.replace(Some(closure));
+                }),
+                enc!((self => s) move |notification| s.notify_with_notification(notification)),
+                Rc::clone(&self.cfg.base_path),
+            ),
+        }
+        let link_listener_closure = routing::setup_link_listener(
+            enc!((self => s) move |notification| s.notify_with_notification(notification)),
+            mode,
+            Rc::clone(&self.cfg.base_path),
+        );
+        self.data
+            .link_listener_closure
+            .replace(Some(link_listener_closure));
+    }
+
+    /// Ask navigation guards also before the page is unloaded - see `Orders::guard_navigation`.
+    #[cfg(feature = "routing")]
+    fn setup_beforeunload_guard(&self) {
+        use enclose::enc;
+        if self.data.unmounted.get() || self.data.beforeunload_closure.borrow().is_some() {
+            return;
+        }
+        routing::setup_beforeunload_listener(
+            enc!((self => s) move |closure| {
+                s.data.beforeunload_closure.replace(Some(closure));
+            }),
+            enc!((self => s) move |notification| s.notify_with_notification(notification)),
+        );
+    }
+
+    #[cfg(feature = "routing")]
+    fn teardown_routing(&self) {
+        if let Some(closure) = self.data.popstate_closure.take() {
+            routing::remove_popstate_listener(&closure);
+        }
+        if let Some(closure) = self.data.hashchange_closure.take() {
+            routing::remove_hashchange_listener(&closure);
+        }
+        if let Some(closure) = self.data.link_listener_closure.take() {
+            routing::remove_link_listener(&closure);
+        }
+    }
+
+    /// Stop the `App` and release all resources owned by it.
+    ///
+    /// - Routing listeners (`popstate` or `hashchange`, link clicks, `beforeunload` and `scroll`) are removed.
+    /// - All subscriptions are removed and cmds and streams started without a handle are aborted.
+    /// - The scheduled render and pending `after_next_render` callbacks are cancelled.
+    /// - The model is dropped (together with all handles stored in it) and the mount point is cleared.
+    ///
+    /// Messages and notifications sent to the `App` after unmounting are ignored.
+    /// The `App` doesn't reference itself after unmounting so it's dropped together with its last clone.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let app = App::start("app", init, update, view);
+    /// // ...
+    /// app.unmount();
+    /// ```
+    pub fn unmount(&self) {
+        if self.data.unmounted.replace(true) {
+            return;
+        }
+        self.cancel_scheduled_render();
+
+        #[cfg(feature = "routing")]
+        {
+            self.teardown_routing();
+            if let Some(closure) = self.data.beforeunload_closure.take() {
+                routing::remove_beforeunload_listener(&closure);
+            }
+            if let Some(closure) = self.data.scroll_closure.take() {
+                routing::remove_scroll_listener(&closure);
+            }
+        }
+
+        self.data.sub_manager.borrow().unsubscribe_all();
+        self.data.cmd_manager.borrow().abort_all();
+        self.data.stream_manager.borrow().abort_all();
+        drop(self.data.after_next_render_callbacks.take());
+        drop(self.data.middlewares.take());
+        drop(self.data.window_events.take());
+        drop(self.data.panic_handler_guard.take());
+        drop(self.data.last_message_recorder.take());
+        #[cfg(feature = "time-travel")]
+        if let Some(recorder) = self.data.update_recorder.take() {
+            recorder.stop();
+        }
+        #[cfg(feature = "serde")]
+        drop(self.data.persister.take());
+        drop(
+            self.data
+                .window_event_handler_manager
+                .replace(EventHandlerManager::new()),
+        );
+        // Dropping the old vdom detaches its event listeners.
+        drop(self.data.root_el.take());
+        // The model is still borrowed when `unmount` is called from `update`;
+        // it's dropped in `process_queue_message` in that case.
+        if let Ok(mut model) = self.data.model.try_borrow_mut() {
+            drop(model.take());
+        }
+
+        while let Some(child) = self.cfg.mount_point.first_child() {
+            self.cfg
+                .mount_point
+                .remove_child(&child)
+                .expect("remove mount point's child");
+        }
+    }
+
+    /// Add a middleware. Middlewares are invoked in the order they were added,
+    /// before the message is passed to `update`.
+    ///
+    /// The returned `MiddlewareAction` determines whether the message is passed on, replaced,
+    /// dropped or delayed. Dropped and delayed messages don't schedule a render.
+    ///
+    /// _Note:_ Don't call `App::update` directly from a middleware because the model is borrowed.
+    /// Use `orders.clone_app()` to add a middleware from `init` or `update`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// app.add_middleware(|msg: &Msg, ctx: MiddlewareContext<Model>| match msg {
+    ///     Msg::DeleteUser(_) if !ctx.model.is_admin => MiddlewareAction::Drop,
+    ///     Msg::Search(query) => {
+    ///         log!("search", query);
+    ///         MiddlewareAction::Continue
+    ///     }
+    ///     _ => MiddlewareAction::Continue,
+    /// });
+    /// ```
+    pub fn add_middleware(
+        &self,
+        middleware: impl Fn(&Ms, MiddlewareContext<Mdl>) -> MiddlewareAction<Ms> + 'static,
+    ) {
+        self.data.middlewares.borrow_mut().push(Rc::new(middleware));
+    }
+
+    /// Set the function that creates window event handlers from the model.
+    ///
+    /// It's invoked immediately and after each render. Listeners of the previous handlers are reused
+    /// (or removed when they aren't needed anymore) so the handlers can depend on the model -
+    /// e.g. keyboard shortcuts enabled only in some pages.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// fn window_events(model: &Model) -> Vec<EventHandler<Msg>> {
+    ///     let mut handlers = vec![ev(Ev::Resize, |_| Msg::Resized)];
+    ///     if model.editor_open {
+    ///         handlers.push(keyboard_ev(Ev::KeyDown, Msg::EditorKeyDown));
+    ///     }
+    ///     handlers
+    /// }
+    ///
+    /// let app = App::start("app", init, update, view);
+    /// app.window_events(window_events);
+    /// ```
+    pub fn window_events(&self, window_events: impl Fn(&Mdl) -> Vec<EventHandler<Ms>> + 'static) {
+        self.data
+            .window_events
+            .replace(Some(Rc::new(window_events)));
+        self.update_window_event_handlers();
     }
 
     /// Invoke your `update` function with provided message.
@@ -258,6 +569,10 @@ where
         }
 
         while let Some(effect) = queue.pop_front() {
+            // `unmount` may have been called while processing the previous effect.
+            if self.data.unmounted.get() {
+                return;
+            }
             match effect {
                 Effect::Msg(msg) => {
                     let mut new_effects = self.process_queue_message(msg);
@@ -275,68 +590,8 @@ where
         }
     }
 
-    /// Bootstrap the dom at startup with the vdom by taking over all children of the mount point and
-    /// replacing them with the vdom.
-    fn bootstrap_vdom(&self) -> El<Ms> {
-        // "new" name is for consistency with `update` function.
-        // this section parent is a placeholder, so we can iterate over children
-        // in a way consistent with patching code.
-        let mut new = El::empty(Tag::Placeholder);
-
-        // Map the DOM's elements onto the virtual DOM.
-        // Construct a vdom from the root element. Subsequently strip the workspace so that we
-        // can recreate it later - this is a kind of simple way to avoid missing nodes (but
-        // not entirely correct).
-        // TODO: 1) Please refer to [issue #277](https://github.com/seed-rs/seed/issues/277)
-        let mut dom_nodes: El<Ms> = (&self.cfg.mount_point).into();
-        #[cfg(debug_assertions)]
-        dom_nodes.warn_about_script_tags();
-
-        dom_nodes.strip_ws_nodes_from_self_and_children();
-
-        // Replace the root dom with a placeholder tag and move the children from the root element
-        // to the newly created root. Uses `Placeholder` to mimic update logic.
-        new.children = dom_nodes.children;
-
-        // Recreate the needed nodes.
-        // TODO: Please refer to [issue #277](https://github.com/seed-rs/seed/issues/277)
-        // TODO: Look into how the 0.7 API changes removing of MountType takeover check interact
-        // with the uses of `rerender_vdom` and `bootstrap_vdom`
-        virtual_dom_bridge::assign_ws_nodes_to_el(&util::document(), &mut new);
-
-        // Remove all old elements. We'll swap them out with the newly created elements later.
-        // This maneuver will effectively allow us to remove everything in the mount and thus
-        // takeover the mount point.
-        while let Some(child) = self.cfg.mount_point.first_child() {
-            self.cfg
-                .mount_point
-                .remove_child(&child)
-                .expect("No problem removing node from parent.");
-        }
-
-        // Attach all top-level elements to the mount point if present. This means that we have
-        // effectively taken full control of everything within the mounting element.
-        for child in &mut new.children {
-            match child {
-                Node::Element(child_el) => {
-                    virtual_dom_bridge::attach_el_and_children(
-                        child_el,
-                        &self.cfg.mount_point,
-                        &self.mailbox(),
-                    );
-                }
-                Node::Text(top_child_text) => {
-                    virtual_dom_bridge::attach_text_node(top_child_text, &self.cfg.mount_point);
-                }
-                Node::Empty | Node::NoChange => (),
-            }
-        }
-
-        new
-    }
-
     fn rerender_vdom(&self) {
-        if std::thread::panicking() {
+        if std::thread::panicking() || self.data.unmounted.get() {
             return;
         }
 
@@ -347,26 +602,32 @@ where
         let mut new = El::empty(Tag::Placeholder);
         new.children = (self.cfg.view)(self.data.model.borrow().as_ref().unwrap()).into_nodes();
 
-        let old = self
-            .data
-            .root_el
-            .borrow_mut()
-            .take()
-            .expect("missing root element");
-
-        patch::patch_els(
-            &self.cfg.document,
-            &self.mailbox(),
-            &self.clone(),
-            &self.cfg.mount_point,
-            old.children.into_iter(),
-            new.children.iter_mut(),
-        );
+        let old = self.data.root_el.borrow_mut().take();
+
+        match old {
+            Some(old) => patch::patch_els(
+                &self.cfg.document,
+                &self.mailbox(),
+                &self.clone(),
+                &self.cfg.mount_point,
+                old.children.into_iter(),
+                new.children.iter_mut(),
+            ),
+            // The first render - reuse the existing (e.g. prerendered) content of the mount point.
+            None => patch::hydrate(
+                &self.cfg.document,
+                &self.mailbox(),
+                &self.cfg.mount_point,
+                new.children.iter_mut(),
+            ),
+        }
 
         // Now that we've re-rendered, replace our stored El with the new one;
         // it will be used as the old El next time.
         self.data.root_el.borrow_mut().replace(new);
 
+        self.update_window_event_handlers();
+
         // Execute `after_next_render_callbacks`.
 
         let render_info = self.data.render_info.take().map_or(
@@ -391,6 +652,27 @@ where
         );
     }
 
+    /// Create window event handlers by the function set by `App::window_events`
+    /// and attach them to the window; unused listeners are removed.
+    fn update_window_event_handlers(&self) {
+        let window_events = self.data.window_events.borrow().clone();
+        let Some(window_events) = window_events else {
+            return;
+        };
+        let event_handlers = window_events(
+            self.data
+                .model
+                .borrow()
+                .as_ref()
+                .expect("get model for window events"),
+        );
+        let mut new_manager = EventHandlerManager::with_event_handlers(event_handlers);
+        let mut old_manager = self.data.window_event_handler_manager.borrow_mut();
+        new_manager.attach_listeners(window(), Some(&mut *old_manager), &self.mailbox());
+        // Listeners that haven't been reused are detached on drop.
+        *old_manager = new_manager;
+    }
+
     fn process_queue_notification(&self, notification: &Notification) -> VecDeque<Effect<Ms>> {
         self.data
             .sub_manager
@@ -402,18 +684,44 @@ where
     }
 
     fn process_queue_message(&self, message: Option<Ms>) -> VecDeque<Effect<Ms>> {
+        self.process_queue_message_from_middleware(message, 0)
+    }
+
+    /// Pass the message through middlewares starting with the one at index `first_middleware`
+    /// and then to `update`.
+    fn process_queue_message_from_middleware(
+        &self,
+        message: Option<Ms>,
+        first_middleware: usize,
+    ) -> VecDeque<Effect<Ms>> {
         let mut orders = OrdersContainer::new(self.clone());
 
         if let Some(message) = message {
-            for l in self.data.msg_listeners.borrow().iter() {
-                (l)(&message);
+            let Some(message) = self.apply_middlewares(message, first_middleware) else {
+                // The message has been dropped or delayed by a middleware.
+                return VecDeque::new();
+            };
+
+            if let Some(recorder) = self.data.last_message_recorder.borrow().as_ref() {
+                recorder(&message);
             }
+            #[cfg(feature = "time-travel")]
+            self.record_message(&message);
 
             (self.cfg.update)(
                 message,
                 self.data.model.borrow_mut().as_mut().unwrap(),
                 &mut orders,
             );
+
+            if self.data.unmounted.get() {
+                // `unmount` has been called from `update`.
+                drop(self.data.model.take());
+                return VecDeque::new();
+            }
+
+            #[cfg(feature = "serde")]
+            self.schedule_persistence();
         }
 
         match orders.should_render {
@@ -427,6 +735,51 @@ where
         orders.effects
     }
 
+    #[cfg(feature = "time-travel")]
+    fn record_message(&self, message: &Ms) {
+        let recorder = self.data.update_recorder.borrow().clone();
+        if let Some(recorder) = recorder {
+            recorder.record(
+                message,
+                self.data
+                    .model
+                    .borrow()
+                    .as_ref()
+                    .expect("get model for recorder"),
+            );
+        }
+    }
+
+    fn apply_middlewares(&self, mut message: Ms, first_middleware: usize) -> Option<Ms> {
+        // Clone middlewares so they can be added also from a middleware.
+        let middlewares = self.data.middlewares.borrow().clone();
+
+        for (index, middleware) in middlewares.iter().enumerate().skip(first_middleware) {
+            let model = self.data.model.borrow();
+            let context = MiddlewareContext {
+                model: model.as_ref().expect("get model for middleware"),
+            };
+            let action = middleware(&message, context);
+            drop(model);
+
+            match action {
+                MiddlewareAction::Continue => (),
+                MiddlewareAction::Replace(new_message) => message = new_message,
+                MiddlewareAction::Drop => return None,
+                MiddlewareAction::Delay(ms) => {
+                    let cmd = TimeoutFuture::new(ms).map(enclose!((self => s) move |_| {
+                        s.process_effect_queue(
+                            s.process_queue_message_from_middleware(Some(message), index + 1),
+                        );
+                    }));
+                    self.data.cmd_manager.borrow().perform_cmd(cmd);
+                    return None;
+                }
+            }
+        }
+        Some(message)
+    }
+
     fn schedule_render(&self) {
         let mut scheduled_render_handle = self.data.scheduled_render_handle.borrow_mut();
 
@@ -451,3 +804,273 @@ where
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate as seed;
+    use crate::prelude::*;
+    use enclose::enc;
+    use std::{cell::Cell, rc::Rc};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Clone)]
+    struct Msg;
+
+    #[wasm_bindgen_test]
+    fn unmount_releases_listeners() {
+        let updates = Rc::new(Cell::new(0));
+        let mount_point = seed::document().create_element("div").unwrap();
+        seed::body().append_child(&mount_point).unwrap();
+
+        let app: App<Msg, (), Node<Msg>> = App::start(
+            mount_point.clone(),
+            |_, orders| {
+                orders
+                    .subscribe(|_: &'static str| Msg)
+                    .stream(streams::interval(10, || Msg))
+                    .after_next_render(|_| Msg);
+            },
+            enc!((updates) move |_, _, _| updates.set(updates.get() + 1)),
+            |_| button![ev(Ev::Click, |_| Msg)],
+        );
+        let button = mount_point
+            .first_element_child()
+            .unwrap()
+            .dyn_into::<web_sys::HtmlElement>()
+            .unwrap();
+        let updates_before_unmount = updates.get();
+
+        app.unmount();
+
+        assert_eq!(mount_point.child_nodes().length(), 0);
+        assert!(app.data.model.borrow().is_none());
+        assert!(app.data.root_el.borrow().is_none());
+        assert!(app.data.after_next_render_callbacks.borrow().is_empty());
+        assert!(app.data.link_listener_closure.borrow().is_none());
+        assert!(app.data.popstate_closure.borrow().is_none());
+
+        button.click();
+        app.notify("notification");
+        app.update(Msg);
+        assert_eq!(updates.get(), updates_before_unmount);
+    }
+
+    #[wasm_bindgen_test]
+    fn middlewares_replace_and_drop_messages() {
+        let received = Rc::new(Cell::new(0));
+        let mount_point = seed::document().create_element("div").unwrap();
+
+        let app: App<u32, (), Node<u32>> = App::start(
+            mount_point,
+            |_, _| (),
+            enc!((received) move |msg, _, _| received.set(msg)),
+            |_| empty![],
+        );
+        app.add_middleware(|msg: &u32, _| match msg {
+            0 => MiddlewareAction::Drop,
+            1 => MiddlewareAction::Replace(10),
+            _ => MiddlewareAction::Continue,
+        });
+        app.add_middleware(|msg: &u32, _| {
+            if *msg == 10 {
+                MiddlewareAction::Replace(11)
+            } else {
+                MiddlewareAction::Continue
+            }
+        });
+
+        app.update(2);
+        assert_eq!(received.get(), 2);
+        app.update(0);
+        assert_eq!(received.get(), 2);
+        app.update(1);
+        assert_eq!(received.get(), 11);
+    }
+
+    #[wasm_bindgen_test]
+    fn window_events_are_diffed_on_render() {
+        let mount_point = seed::document().create_element("div").unwrap();
+        let app: App<u32, u32, Node<u32>> = App::start(
+            mount_point,
+            |_, _| 0,
+            |msg, model, orders| {
+                *model += msg;
+                orders.force_render_now();
+            },
+            |_| empty![],
+        );
+        app.window_events(|model| {
+            if *model < 2 {
+                vec![raw_ev("seed-test-window-event", |_| 1)]
+            } else {
+                vec![]
+            }
+        });
+
+        let dispatch = || {
+            let event = web_sys::Event::new("seed-test-window-event").unwrap();
+            seed::window().dispatch_event(&event).unwrap();
+        };
+        dispatch();
+        dispatch();
+        // The listener has been removed after the second event.
+        dispatch();
+        assert_eq!(*app.data.model.borrow(), Some(2));
+        app.unmount();
+    }
+
+    #[cfg(feature = "time-travel")]
+    #[wasm_bindgen_test]
+    fn time_travel_jumps_and_replays() {
+        let mount_point = seed::document().create_element("div").unwrap();
+        let app: App<u32, u32, Node<u32>> = App::start(
+            mount_point,
+            |_, _| 0,
+            |msg, model, _| *model += msg,
+            |_| empty![],
+        );
+        let time_travel = app.enable_time_travel();
+
+        app.update(1);
+        app.update(2);
+        assert_eq!(time_travel.messages(), vec![1, 2]);
+
+        time_travel.jump_to(1);
+        assert_eq!(*app.data.model.borrow(), Some(1));
+        time_travel.jump_to(2);
+        assert_eq!(*app.data.model.borrow(), Some(3));
+
+        time_travel.replay();
+        assert_eq!(*app.data.model.borrow(), Some(3));
+        assert_eq!(time_travel.len(), 2);
+
+        time_travel.jump_to(0);
+        app.update(5);
+        assert_eq!(time_travel.messages(), vec![5]);
+        assert_eq!(*app.data.model.borrow(), Some(5));
+    }
+
+    #[cfg(feature = "time-travel")]
+    #[wasm_bindgen_test]
+    fn time_travel_replay_skips_middlewares() {
+        let mount_point = seed::document().create_element("div").unwrap();
+        let app: App<u32, u32, Node<u32>> = App::start(
+            mount_point,
+            |_, _| 0,
+            |msg, model, _| *model += msg,
+            |_| empty![],
+        );
+        let time_travel = app.enable_time_travel();
+        app.add_middleware(|msg: &u32, _| MiddlewareAction::Replace(msg * 10));
+
+        app.update(1);
+        assert_eq!(time_travel.messages(), vec![10]);
+
+        time_travel.replay();
+        assert_eq!(*app.data.model.borrow(), Some(10));
+        assert_eq!(time_travel.messages(), vec![10]);
+    }
+
+    #[cfg(feature = "routing")]
+    #[wasm_bindgen_test]
+    async fn hash_routing_mode_intercepts_hash_links() {
+        let mount_point = seed::document().create_element("div").unwrap();
+        seed::body().append_child(&mount_point).unwrap();
+
+        let app: App<subs::UrlChanged, Vec<String>, Node<subs::UrlChanged>> = App::start(
+            mount_point.clone(),
+            |_, orders| {
+                orders.subscribe(|url_changed: subs::UrlChanged| url_changed);
+                Vec::new()
+            },
+            |subs::UrlChanged(url), model, _| *model = url.hash_path().to_vec(),
+            |_| a![attrs! {At::Href => "#/admin/report"}],
+        );
+        app.routing_mode(RoutingMode::Hash);
+        assert!(app.data.popstate_closure.borrow().is_none());
+        assert!(app.data.hashchange_closure.borrow().is_some());
+
+        mount_point
+            .first_element_child()
+            .unwrap()
+            .dyn_into::<web_sys::HtmlElement>()
+            .unwrap()
+            .click();
+        gloo_timers::future::TimeoutFuture::new(10).await;
+        assert_eq!(seed::window().location().hash().unwrap(), "#/admin/report");
+        assert_eq!(
+            app.data.model.borrow().as_deref(),
+            Some(["admin".to_owned(), "report".to_owned()].as_slice())
+        );
+
+        app.unmount();
+        assert!(app.data.hashchange_closure.borrow().is_none());
+        seed::window().location().set_hash("").unwrap();
+        mount_point.remove();
+    }
+
+    #[cfg(feature = "routing")]
+    #[derive(Clone)]
+    enum RoutingMsg {
+        NavigationRequested(subs::Navigation),
+        UrlChanged(subs::UrlChanged),
+    }
+
+    #[cfg(feature = "routing")]
+    #[wasm_bindgen_test]
+    fn navigation_guards_defer_and_block() {
+        type Model = (Option<subs::Navigation>, Vec<String>);
+        let mount_point = seed::document().create_element("div").unwrap();
+        let original_url = Url::current();
+
+        let app: App<RoutingMsg, Model, Node<RoutingMsg>> = App::start(
+            mount_point,
+            |_, orders| {
+                orders
+                    .guard_navigation(RoutingMsg::NavigationRequested)
+                    .subscribe(RoutingMsg::UrlChanged);
+                (None, Vec::new())
+            },
+            |msg, (pending, path), _| match msg {
+                RoutingMsg::NavigationRequested(navigation) => {
+                    navigation.defer();
+                    *pending = Some(navigation);
+                }
+                RoutingMsg::UrlChanged(subs::UrlChanged(url)) => *path = url.path().to_vec(),
+            },
+            |_| empty![],
+        );
+        assert!(app.data.beforeunload_closure.borrow().is_some());
+        let take_pending = || {
+            app.data
+                .model
+                .borrow_mut()
+                .as_mut()
+                .unwrap()
+                .0
+                .take()
+                .unwrap()
+        };
+
+        app.notify(subs::UrlRequested::new(Url::new().set_path(["allowed"])));
+        let navigation = take_pending();
+        assert!(navigation.is_deferred());
+        assert_eq!(navigation.kind(), subs::NavigationKind::Request);
+        assert_eq!(Url::current().path(), original_url.path());
+
+        navigation.allow();
+        assert_eq!(Url::current().path(), ["allowed"]);
+        assert_eq!(app.data.model.borrow().as_ref().unwrap().1, ["allowed"]);
+
+        app.notify(subs::UrlRequested::new(Url::new().set_path(["blocked"])));
+        take_pending().block();
+        assert_eq!(Url::current().path(), ["allowed"]);
+        assert_eq!(app.data.model.borrow().as_ref().unwrap().1, ["allowed"]);
+
+        app.unmount();
+        assert!(app.data.beforeunload_closure.borrow().is_none());
+        original_url.go_and_replace();
+    }
+}
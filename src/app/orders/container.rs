@@ -1,12 +1,15 @@
-use crate::app::cmd_manager::CmdManager;
+use crate::app::cmd_manager::{CmdManager, KeyedCmds};
 use crate::app::orders::{proxy::OrdersProxy, Orders};
 use crate::app::stream_manager::StreamManager;
+use crate::app::sub_manager::SubManager;
 use crate::app::{
-    App, CmdHandle, Effect, Notification, RenderInfo, ShouldRender, StreamHandle, SubHandle,
+    App, CmdHandle, Effect, IntoOptionMsg, Notification, RenderInfo, ShouldRender, StreamHandle,
+    SubHandle,
 };
 use crate::virtual_dom::IntoNodes;
-use futures::future::FutureExt;
-use futures::stream::{Stream, StreamExt};
+use enclose::enc;
+use futures::future::{abortable, FutureExt, LocalBoxFuture};
+use futures::stream::{LocalBoxStream, Stream, StreamExt};
 use std::{any::Any, collections::VecDeque, convert::identity, future::Future, rc::Rc};
 
 #[allow(clippy::module_name_repetitions)]
@@ -18,7 +21,29 @@ where
 {
     pub(crate) should_render: ShouldRender,
     pub(crate) effects: VecDeque<Effect<Ms>>,
-    app: App<Ms, Mdl, INodes>,
+    pub(crate) runtime: Runtime<Ms, Mdl, INodes>,
+}
+
+/// Executes effects that can't be just queued - cmds, streams, subscriptions, etc.
+pub(crate) enum Runtime<Ms, Mdl, INodes>
+where
+    Ms: 'static,
+    Mdl: 'static,
+    INodes: IntoNodes<Ms>,
+{
+    App(App<Ms, Mdl, INodes>),
+    /// There is no running `App` (e.g. during server-side rendering or in tests) -
+    /// effects are only recorded.
+    Detached(Detached<Ms>),
+}
+
+/// Effects recorded by a detached `OrdersContainer`.
+pub(crate) struct Detached<Ms: 'static> {
+    pub cmds: Vec<LocalBoxFuture<'static, Option<Ms>>>,
+    pub keyed_cmds: KeyedCmds,
+    pub streams: Vec<LocalBoxStream<'static, Option<Ms>>>,
+    pub sub_manager: SubManager<Ms>,
+    pub after_next_render_callbacks: Vec<Box<dyn FnOnce(RenderInfo) -> Option<Ms>>>,
 }
 
 impl<Ms, Mdl, INodes> OrdersContainer<Ms, Mdl, INodes>
@@ -29,7 +54,26 @@ where
         Self {
             should_render: ShouldRender::Render,
             effects: VecDeque::<Effect<Ms>>::new(),
-            app,
+            runtime: Runtime::App(app),
+        }
+    }
+
+    /// Creates a container without `App`.
+    ///
+    /// Messages, notifications and render requests are collected as usual.
+    /// Cmds, streams, subscriptions and `after_next_render` callbacks are only recorded
+    /// because there is no runtime to execute them.
+    pub(crate) fn new_detached() -> Self {
+        Self {
+            should_render: ShouldRender::Render,
+            effects: VecDeque::<Effect<Ms>>::new(),
+            runtime: Runtime::Detached(Detached {
+                cmds: Vec::new(),
+                keyed_cmds: KeyedCmds::default(),
+                streams: Vec::new(),
+                sub_manager: SubManager::new(),
+                after_next_render_callbacks: Vec::new(),
+            }),
         }
     }
 }
@@ -77,134 +121,205 @@ where
         self
     }
 
-    fn perform_cmd<MsU: 'static>(&mut self, cmd: impl Future<Output = MsU> + 'static) -> &mut Self {
-        let app = self.app.clone();
-
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Cmds can return only Msg, Option<Msg> or ()!",
-            Box
-        );
-
-        let cmd = cmd.map(move |msg| app.mailbox().send(handler(msg)));
-        CmdManager::perform_cmd(cmd);
+    fn perform_cmd<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self {
+        match &mut self.runtime {
+            Runtime::App(app) => {
+                let cmd = cmd.map(enc!((app) move |msg| app.mailbox().send(msg.into_option_msg())));
+                app.data.cmd_manager.borrow().perform_cmd(cmd);
+            }
+            Runtime::Detached(detached) => {
+                detached
+                    .cmds
+                    .push(cmd.map(|msg| msg.into_option_msg()).boxed_local());
+            }
+        }
         self
     }
 
-    fn perform_cmd_with_handle<MsU: 'static>(
+    fn perform_cmd_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         cmd: impl Future<Output = MsU> + 'static,
     ) -> CmdHandle {
-        let app = self.app.clone();
+        match &mut self.runtime {
+            Runtime::App(app) => {
+                let cmd = cmd.map(enc!((app) move |msg| app.mailbox().send(msg.into_option_msg())));
+                CmdManager::perform_cmd_with_handle(cmd)
+            }
+            Runtime::Detached(detached) => {
+                let (cmd, handle) = abortable(cmd.map(|msg| msg.into_option_msg()));
+                // An aborted cmd doesn't produce any message.
+                detached
+                    .cmds
+                    .push(cmd.map(|result| result.ok().flatten()).boxed_local());
+                CmdHandle(handle)
+            }
+        }
+    }
+
+    fn perform_cmd_keyed<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        key: impl Into<String>,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self {
+        match &mut self.runtime {
+            Runtime::App(app) => {
+                let cmd_manager = app.data.cmd_manager.borrow();
+                let cmd = cmd_manager.keyed_cmds.register(key.into(), cmd).map(
+                    enc!((app) move |output| {
+                        // Aborted cmds don't send anything.
+                        if let Some(msg) = output {
+                            app.mailbox().send(msg.into_option_msg());
+                        }
+                    }),
+                );
+                cmd_manager.perform_cmd(cmd);
+            }
+            Runtime::Detached(detached) => {
+                let cmd = detached.keyed_cmds.register(key.into(), cmd);
+                detached.cmds.push(
+                    cmd.map(|output| output.and_then(|msg| msg.into_option_msg()))
+                        .boxed_local(),
+                );
+            }
+        }
+        self
+    }
 
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Cmds can return only Msg, Option<Msg> or ()!",
-            Box
-        );
+    fn cancel_cmd(&mut self, key: &str) -> &mut Self {
+        match &self.runtime {
+            Runtime::App(app) => app.data.cmd_manager.borrow().keyed_cmds.cancel(key),
+            Runtime::Detached(detached) => detached.keyed_cmds.cancel(key),
+        }
+        self
+    }
 
-        let cmd = cmd.map(move |msg| app.mailbox().send(handler(msg)));
-        CmdManager::perform_cmd_with_handle(cmd)
+    fn is_cmd_pending(&self, key: &str) -> bool {
+        match &self.runtime {
+            Runtime::App(app) => app.data.cmd_manager.borrow().keyed_cmds.is_pending(key),
+            Runtime::Detached(detached) => detached.keyed_cmds.is_pending(key),
+        }
     }
 
     fn clone_app(&self) -> App<Self::AppMs, Self::Mdl, Self::INodes> {
-        self.app.clone()
+        match &self.runtime {
+            Runtime::App(app) => app.clone(),
+            Runtime::Detached(_) => panic!("`App` isn't available in a detached `OrdersContainer`"),
+        }
     }
 
     fn msg_mapper(&self) -> Rc<dyn Fn(Ms) -> Self::AppMs> {
         Rc::new(identity)
     }
 
-    fn after_next_render<MsU: 'static>(
+    fn msg_sender(&self) -> Rc<dyn Fn(Option<Ms>)> {
+        match &self.runtime {
+            Runtime::App(app) => Rc::new(enc!((app) move |msg| app.update_with_option(msg))),
+            Runtime::Detached(_) => Rc::new(|_| ()),
+        }
+    }
+
+    fn after_next_render<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         callback: impl FnOnce(RenderInfo) -> MsU + 'static,
     ) -> &mut Self {
-        let callback = map_callback_return_to_option_ms!(
-            dyn FnOnce(RenderInfo) -> Option<Ms>,
-            callback,
-            "Callback can return only Msg, Option<Msg> or ()!",
-            Box
-        );
-
-        self.app
-            .data
-            .after_next_render_callbacks
-            .borrow_mut()
-            .push(callback);
+        let callback: Box<dyn FnOnce(RenderInfo) -> Option<Ms>> =
+            Box::new(move |render_info| callback(render_info).into_option_msg());
+
+        match &mut self.runtime {
+            Runtime::App(app) => app
+                .data
+                .after_next_render_callbacks
+                .borrow_mut()
+                .push(callback),
+            Runtime::Detached(detached) => detached.after_next_render_callbacks.push(callback),
+        }
         self
     }
 
-    fn subscribe<MsU: 'static, SubMs: 'static + Clone>(
+    fn subscribe<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker, SubMs: 'static + Clone>(
         &mut self,
         handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
     ) -> &mut Self {
-        #[allow(clippy::redundant_closure)]
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(SubMs) -> Option<Ms>,
-            handler.clone(),
-            "Handler can return only Msg, Option<Msg> or ()!",
-            Rc
-        );
-
-        #[allow(clippy::redundant_closure)]
-        self.app
-            .data
-            .sub_manager
-            .borrow_mut()
-            .subscribe(move |sub_ms| handler(sub_ms));
+        match &mut self.runtime {
+            Runtime::App(app) => app
+                .data
+                .sub_manager
+                .borrow_mut()
+                .subscribe(move |sub_ms| handler(sub_ms).into_option_msg()),
+            Runtime::Detached(detached) => {
+                detached
+                    .sub_manager
+                    .subscribe(move |sub_ms| handler(sub_ms).into_option_msg());
+            }
+        }
         self
     }
 
-    fn subscribe_with_handle<MsU: 'static, SubMs: 'static + Clone>(
+    fn subscribe_with_handle<
+        MsU: IntoOptionMsg<Ms, Marker> + 'static,
+        Marker,
+        SubMs: 'static + Clone,
+    >(
         &mut self,
         handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
     ) -> SubHandle {
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(SubMs) -> Option<Ms>,
-            handler.clone(),
-            "Handler can return only Msg, Option<Msg> or ()!",
-            Rc
-        );
-
-        #[allow(clippy::redundant_closure)]
-        self.app
-            .data
-            .sub_manager
-            .borrow_mut()
-            .subscribe_with_handle(move |sub_ms| handler(sub_ms))
-    }
-
-    fn stream<MsU: 'static>(&mut self, stream: impl Stream<Item = MsU> + 'static) -> &mut Self {
-        let app = self.app.clone();
-
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Streams can stream only Msg, Option<Msg> or ()!",
-            Box
-        );
-
-        let stream = stream.map(move |msg| app.mailbox().send(handler(msg)));
-        StreamManager::stream(stream);
+        match &mut self.runtime {
+            Runtime::App(app) => app
+                .data
+                .sub_manager
+                .borrow_mut()
+                .subscribe_with_handle(move |sub_ms| handler(sub_ms).into_option_msg()),
+            Runtime::Detached(detached) => detached
+                .sub_manager
+                .subscribe_with_handle(move |sub_ms| handler(sub_ms).into_option_msg()),
+        }
+    }
+
+    fn stream<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        stream: impl Stream<Item = MsU> + 'static,
+    ) -> &mut Self {
+        match &mut self.runtime {
+            Runtime::App(app) => {
+                let stream =
+                    stream.map(enc!((app) move |msg| app.mailbox().send(msg.into_option_msg())));
+                app.data.stream_manager.borrow().stream(stream);
+            }
+            Runtime::Detached(detached) => {
+                detached
+                    .streams
+                    .push(stream.map(|msg| msg.into_option_msg()).boxed_local());
+            }
+        }
         self
     }
 
-    fn stream_with_handle<MsU: 'static>(
+    fn stream_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         stream: impl Stream<Item = MsU> + 'static,
     ) -> StreamHandle {
-        let app = self.app.clone();
-
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Streams can stream only Msg, Option<Msg> or ()!",
-            Box
-        );
+        match &mut self.runtime {
+            Runtime::App(app) => {
+                let stream =
+                    stream.map(enc!((app) move |msg| app.mailbox().send(msg.into_option_msg())));
+                StreamManager::stream_with_handle(stream)
+            }
+            Runtime::Detached(detached) => {
+                let (stream, handle) =
+                    futures::stream::abortable(stream.map(|msg| msg.into_option_msg()));
+                detached.streams.push(stream.boxed_local());
+                StreamHandle(handle)
+            }
+        }
+    }
 
-        let stream = stream.map(move |msg| app.mailbox().send(handler(msg)));
-        StreamManager::stream_with_handle(stream)
+    fn clone_base_path(&self) -> Rc<[String]> {
+        match &self.runtime {
+            Runtime::App(app) => Rc::clone(&app.cfg.base_path),
+            Runtime::Detached(_) => Rc::from(Vec::new()),
+        }
     }
 }
@@ -1,12 +1,13 @@
-use super::{App, CmdHandle, RenderInfo, StreamHandle, SubHandle};
+use super::{App, CmdHandle, IntoOptionMsg, RenderInfo, StreamHandle, SubHandle};
 use crate::virtual_dom::IntoNodes;
-use futures::stream::Stream;
+use futures::{future::FutureExt, stream::Stream};
+use gloo_timers::future::TimeoutFuture;
 use std::{any::Any, future::Future, rc::Rc};
 
 #[cfg(feature = "routing")]
 use super::subs;
 #[cfg(feature = "routing")]
-use crate::browser::Url;
+use crate::browser::{service::routing, Url};
 
 // @TODO: Add links to doc comment once https://github.com/rust-lang/rust/issues/43466 is resolved
 // or use nightly rustdoc. Applicable to the entire code base.
@@ -78,7 +79,7 @@ pub trait Orders<Ms: 'static> {
 
     /// Execute `cmd` and send its output (if it's `Msg`) to `update` function.
     ///
-    /// Output has to be `Msg`, `Option<Msg>` or `()`.
+    /// Output has to be `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
     ///
     /// # Example
     ///
@@ -88,22 +89,16 @@ pub trait Orders<Ms: 'static> {
     /// ```
     ///
     /// _Note:_: Use the alternative `perform_cmd_with_handle` to control `cmd`'s lifetime.
-    ///
-    /// # Panics
-    ///
-    /// Panics when the output isn't `Msg`, `Option<Msg>` or `()`.
-    ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn perform_cmd<MsU: 'static>(&mut self, cmd: impl Future<Output = MsU> + 'static) -> &mut Self;
+    fn perform_cmd<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self;
 
     /// Execute given `cmd` and send its output (if it's `Msg`) to `update` function.
     /// - Returns `CmdHandle` that you should save to your `Model`.
     ///   The `cmd` is aborted on the handle drop.
     ///
-    /// Output has to be `Msg`, `Option<Msg>` or `()`.
+    /// Output has to be `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
     ///
     /// # Example
     ///
@@ -111,20 +106,61 @@ pub trait Orders<Ms: 'static> {
     ///let timeout_handle = orders.perform_cmd_with_handle(cmds::timeout(2000, || Msg::OnTimeout));
     ///let cmd_handle = orders.perform_cmd_with_handle(async { log!("Hello!") });
     /// ```
+    #[must_use = "cmd is aborted on its handle drop"]
+    fn perform_cmd_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> CmdHandle;
+
+    /// Execute `cmd` like `perform_cmd`, but abort the pending cmd with the same `key` first.
+    /// - Aborted cmds don't send any messages.
+    /// - The runtime keeps the handles, so you don't need to store `CmdHandle`s in your `Model`.
     ///
-    /// # Panics
+    /// Output has to be `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
     ///
-    /// Panics when the output isn't `Msg`, `Option<Msg>` or `()`.
+    /// # Example
     ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
-    #[must_use = "cmd is aborted on its handle drop"]
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn perform_cmd_with_handle<MsU: 'static>(
+    /// ```rust,ignore
+    ///Msg::QueryChanged(query) => {
+    ///    // Only the response for the latest query is processed.
+    ///    orders.perform_cmd_keyed("search", async move { Msg::Searched(search(query).await) });
+    ///}
+    ///Msg::ClearQuery => {
+    ///    orders.cancel_cmd("search");
+    ///}
+    /// ```
+    ///
+    /// _Note:_: Keys are shared by the entire app (including `proxy`-ed `Orders`),
+    /// so prefix keys used in reusable modules.
+    fn perform_cmd_keyed<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
+        key: impl Into<String>,
         cmd: impl Future<Output = MsU> + 'static,
-    ) -> CmdHandle;
+    ) -> &mut Self;
+
+    /// Abort the pending cmd started by `perform_cmd_keyed` with the `key` (if any).
+    fn cancel_cmd(&mut self, key: &str) -> &mut Self;
+
+    /// Returns `true` if the cmd started by `perform_cmd_keyed` with the `key` hasn't finished
+    /// and hasn't been aborted yet.
+    fn is_cmd_pending(&self, key: &str) -> bool;
+
+    /// Send the `msg` after `ms` milliseconds unless another message is debounced
+    /// with the same `key` in the meantime - only the latest one is sent.
+    ///
+    /// It's a keyed cmd, so it can be aborted by `cancel_cmd` and checked by `is_cmd_pending`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    ///Msg::QueryChanged(query) => {
+    ///    model.query = query.clone();
+    ///    orders.debounce_msg("search", 300, Msg::Search(query));
+    ///}
+    /// ```
+    fn debounce_msg(&mut self, key: impl Into<String>, ms: u32, msg: Ms) -> &mut Self {
+        self.perform_cmd_keyed(key, TimeoutFuture::new(ms).map(move |_| msg))
+    }
 
     /// Get app instance. Cloning is cheap because `App` contains only `Rc` fields.
     fn clone_app(&self) -> App<Self::AppMs, Self::Mdl, Self::INodes>;
@@ -188,27 +224,18 @@ pub trait Orders<Ms: 'static> {
     ///
     /// - It's useful when you want to use DOM API or make animations.
     /// - You can call this function multiple times - callbacks will be executed in the same order.
-    /// - Callback has to return `Msg`, `Option<Msg>` or `()`.
+    /// - Callback has to return `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
     ///
     /// _Note:_ [performance.now()](https://developer.mozilla.org/en-US/docs/Web/API/Performance/now)
     ///  is used under the hood to get timestamps.
-    ///
-    /// # Panics
-    ///
-    /// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-    ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn after_next_render<MsU: 'static>(
+    fn after_next_render<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         callback: impl FnOnce(RenderInfo) -> MsU + 'static,
     ) -> &mut Self;
 
     /// Subscribe for messages with the `handler`s input type.
     ///
-    /// Handler has to return `Msg`, `Option<Msg>` or `()`.
+    /// Handler has to return `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
     ///
     /// # Example
     ///
@@ -222,16 +249,7 @@ pub trait Orders<Ms: 'static> {
     /// ```
     ///
     /// _Note:_: Use the alternative `subscribe_with_handle` to control `sub`'s lifetime.
-    ///
-    /// # Panics
-    ///
-    /// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-    ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn subscribe<MsU: 'static, SubMs: 'static + Clone>(
+    fn subscribe<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker, SubMs: 'static + Clone>(
         &mut self,
         handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
     ) -> &mut Self;
@@ -240,7 +258,7 @@ pub trait Orders<Ms: 'static> {
     /// - Returns `SubHandle` that you should save to your `Model`.
     ///   The `sub` is cancelled on the handle drop.
     ///
-    /// Handler has to return `Msg`, `Option<Msg>` or `()`.
+    /// Handler has to return `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
     ///
     /// # Example
     ///
@@ -252,17 +270,12 @@ pub trait Orders<Ms: 'static> {
     ///orders.notify(counter::DoReset);
     ///orders.notify("Hello!");
     /// ```
-    ///
-    /// # Panics
-    ///
-    /// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-    ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
     #[must_use = "subscription is cancelled on its handle drop"]
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn subscribe_with_handle<MsU: 'static, SubMs: 'static + Clone>(
+    fn subscribe_with_handle<
+        MsU: IntoOptionMsg<Ms, Marker> + 'static,
+        Marker,
+        SubMs: 'static + Clone,
+    >(
         &mut self,
         handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
     ) -> SubHandle;
@@ -277,16 +290,10 @@ pub trait Orders<Ms: 'static> {
     /// ```
     ///
     /// _Note:_: Use the alternative `stream_with_handle` to control `stream`'s lifetime.
-    ///
-    /// # Panics
-    ///
-    /// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-    ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn stream<MsU: 'static>(&mut self, stream: impl Stream<Item = MsU> + 'static) -> &mut Self;
+    fn stream<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        stream: impl Stream<Item = MsU> + 'static,
+    ) -> &mut Self;
 
     /// Stream `Msg`, `Option<Msg>` or `()`.
     /// - Returns `StreamHandle` that you should save to your `Model`.
@@ -298,17 +305,8 @@ pub trait Orders<Ms: 'static> {
     ///let timer_handler = orders.stream_with_handle(streams::interval(1000, || Msg::OnTick));
     ///let stream_handler = orders.stream_with_handle(streams::window_event(Ev::Resize, |_| Msg::OnResize));
     /// ```
-    ///
-    /// # Panics
-    ///
-    /// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-    ///
-    /// Stabilisation of issue [391](https://github.com/seed-rs/seed/issues/391) makes this a compile-time error.
     #[must_use = "stream is stopped on its handle drop"]
-    #[allow(clippy::shadow_unrelated)]
-    // @TODO remove `'static`s once `optin_builtin_traits`, `negative_impls`
-    // @TODO or https://github.com/rust-lang/rust/issues/41875 is stable
-    fn stream_with_handle<MsU: 'static>(
+    fn stream_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         stream: impl Stream<Item = MsU> + 'static,
     ) -> StreamHandle;
@@ -328,4 +326,42 @@ pub trait Orders<Ms: 'static> {
     fn request_url(&mut self, url: Url) -> &mut Self {
         self.notify(subs::UrlRequested::new(url))
     }
+
+    /// Decide whether Seed can change the url - on link click, `request_url`,
+    /// back / forward navigation and before the page is unloaded.
+    ///
+    /// The `handler` receives a `subs::Navigation` - `allow`, `block`, `redirect` or `defer` it.
+    /// Undecided navigations are allowed. It's a thin wrapper for
+    /// `orders.subscribe(|subs::NavigationRequested(navigation)| ...)` that also starts
+    /// to listen to `beforeunload`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    ///orders.guard_navigation(Msg::NavigationRequested);
+    ///...
+    ///Msg::NavigationRequested(navigation) => {
+    ///    if !model.has_unsaved_changes() {
+    ///        return navigation.allow();
+    ///    }
+    ///    if navigation.kind() == subs::NavigationKind::Unload {
+    ///        return navigation.block();
+    ///    }
+    ///    navigation.defer();
+    ///    model.confirm_leave_modal = Some(navigation);
+    ///}
+    ///Msg::LeaveConfirmed => {
+    ///    if let Some(navigation) = model.confirm_leave_modal.take() {
+    ///        navigation.allow();
+    ///    }
+    ///}
+    /// ```
+    #[cfg(feature = "routing")]
+    fn guard_navigation<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        handler: impl FnOnce(subs::Navigation) -> MsU + Clone + 'static,
+    ) -> &mut Self {
+        self.notify(routing::GuardBeforeunload);
+        self.subscribe(move |subs::NavigationRequested(navigation)| handler(navigation))
+    }
 }
@@ -1,14 +1,12 @@
 use super::{
-    super::{App, CmdHandle, RenderInfo, StreamHandle, SubHandle},
+    super::{App, CmdHandle, IntoOptionMsg, RenderInfo, StreamHandle, SubHandle},
     Orders, OrdersContainer,
 };
 
-use crate::app::cmd_manager::CmdManager;
-use crate::app::stream_manager::StreamManager;
 use crate::virtual_dom::IntoNodes;
 use futures::future::{Future, FutureExt};
 use futures::stream::{Stream, StreamExt};
-use std::{any::Any, convert::identity, rc::Rc};
+use std::{any::Any, rc::Rc};
 
 #[allow(clippy::module_name_repetitions)]
 pub struct OrdersProxy<'a, Ms, AppMs, Mdl, INodes>
@@ -87,39 +85,48 @@ where
     }
 
     #[allow(clippy::redundant_closure)]
-    fn perform_cmd<MsU: 'static>(&mut self, cmd: impl Future<Output = MsU> + 'static) -> &mut Self {
+    fn perform_cmd<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self {
         let f = self.f.clone();
-        let app = self.clone_app();
-
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Cmds can return only Msg, Option<Msg> or ()!",
-            Box
-        );
 
-        let cmd = cmd.map(move |msg| app.mailbox().send(handler(msg).map(|msg| f(msg))));
-        CmdManager::perform_cmd(cmd);
+        let cmd = cmd.map(move |msg| msg.into_option_msg().map(|msg| f(msg)));
+        self.orders_container.perform_cmd(cmd);
         self
     }
 
-    fn perform_cmd_with_handle<MsU: 'static>(
+    fn perform_cmd_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         cmd: impl Future<Output = MsU> + 'static,
     ) -> CmdHandle {
         let f = self.f.clone();
-        let app = self.clone_app();
 
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Cmds can return only Msg, Option<Msg> or ()!",
-            Box
-        );
+        #[allow(clippy::redundant_closure)]
+        let cmd = cmd.map(move |msg| msg.into_option_msg().map(|msg| f(msg)));
+        self.orders_container.perform_cmd_with_handle(cmd)
+    }
+
+    fn perform_cmd_keyed<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        key: impl Into<String>,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self {
+        let f = self.f.clone();
 
         #[allow(clippy::redundant_closure)]
-        let cmd = cmd.map(move |msg| app.mailbox().send(handler(msg).map(|msg| f(msg))));
-        CmdManager::perform_cmd_with_handle(cmd)
+        let cmd = cmd.map(move |msg| msg.into_option_msg().map(|msg| f(msg)));
+        self.orders_container.perform_cmd_keyed(key, cmd);
+        self
+    }
+
+    fn cancel_cmd(&mut self, key: &str) -> &mut Self {
+        self.orders_container.cancel_cmd(key);
+        self
+    }
+
+    fn is_cmd_pending(&self, key: &str) -> bool {
+        self.orders_container.is_cmd_pending(key)
     }
 
     fn clone_app(&self) -> App<Self::AppMs, Self::Mdl, Self::INodes> {
@@ -132,103 +139,75 @@ where
         Rc::new(move |ms| f(ms))
     }
 
-    fn after_next_render<MsU: 'static>(
+    #[allow(clippy::redundant_closure)]
+    fn msg_sender(&self) -> Rc<dyn Fn(Option<Ms>)> {
+        let (msg_sender, f) = (self.orders_container.msg_sender(), self.f.clone());
+        Rc::new(move |msg: Option<Ms>| msg_sender(msg.map(|msg| f(msg))))
+    }
+
+    fn after_next_render<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         callback: impl FnOnce(RenderInfo) -> MsU + 'static,
     ) -> &mut Self {
-        let callback = map_callback_return_to_option_ms!(
-            dyn FnOnce(RenderInfo) -> Option<Ms>,
-            callback,
-            "Callback can return only Msg, Option<Msg> or ()!",
-            Box
-        );
-
         let f = self.f.clone();
         #[allow(clippy::redundant_closure)]
-        self.clone_app()
-            .data
-            .after_next_render_callbacks
-            .borrow_mut()
-            .push(Box::new(move |render_info| {
-                callback(render_info).map(|ms| f(ms))
-            }));
+        self.orders_container.after_next_render(move |render_info| {
+            callback(render_info).into_option_msg().map(|ms| f(ms))
+        });
         self
     }
 
-    fn subscribe<MsU: 'static, SubMs: 'static + Clone>(
+    fn subscribe<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker, SubMs: 'static + Clone>(
         &mut self,
         handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
     ) -> &mut Self {
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(SubMs) -> Option<Ms>,
-            handler.clone(),
-            "Handler can return only Msg, Option<Msg> or ()!",
-            Rc
-        );
-
         let f = self.f.clone();
         #[allow(clippy::redundant_closure)]
-        self.clone_app()
-            .data
-            .sub_manager
-            .borrow_mut()
-            .subscribe(move |sub_ms| handler(sub_ms).map(|ms| f(ms)));
+        self.orders_container
+            .subscribe(move |sub_ms: SubMs| handler(sub_ms).into_option_msg().map(|ms| f(ms)));
         self
     }
 
-    fn subscribe_with_handle<MsU: 'static, SubMs: 'static + Clone>(
+    fn subscribe_with_handle<
+        MsU: IntoOptionMsg<Ms, Marker> + 'static,
+        Marker,
+        SubMs: 'static + Clone,
+    >(
         &mut self,
         handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
     ) -> SubHandle {
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(SubMs) -> Option<Ms>,
-            handler.clone(),
-            "Handler can return only Msg, Option<Msg> or ()!",
-            Rc
-        );
-
         let f = self.f.clone();
         #[allow(clippy::redundant_closure)]
-        self.clone_app()
-            .data
-            .sub_manager
-            .borrow_mut()
-            .subscribe_with_handle(move |sub_ms| handler(sub_ms).map(|ms| f(ms)))
+        self.orders_container
+            .subscribe_with_handle(move |sub_ms: SubMs| {
+                handler(sub_ms).into_option_msg().map(|ms| f(ms))
+            })
     }
 
-    fn stream<MsU: 'static>(&mut self, stream: impl Stream<Item = MsU> + 'static) -> &mut Self {
+    fn stream<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        stream: impl Stream<Item = MsU> + 'static,
+    ) -> &mut Self {
         let f = self.f.clone();
-        let app = self.clone_app();
-
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Streams can stream only Msg, Option<Msg> or ()!",
-            Box
-        );
 
         #[allow(clippy::redundant_closure)]
-        let stream = stream.map(move |msg| app.mailbox().send(handler(msg).map(|msg| f(msg))));
-        StreamManager::stream(stream);
+        let stream = stream.map(move |msg| msg.into_option_msg().map(|msg| f(msg)));
+        self.orders_container.stream(stream);
         self
     }
 
-    fn stream_with_handle<MsU: 'static>(
+    fn stream_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
         &mut self,
         stream: impl Stream<Item = MsU> + 'static,
     ) -> StreamHandle {
         let f = self.f.clone();
-        let app = self.clone_app();
-
-        let handler = map_callback_return_to_option_ms!(
-            dyn Fn(MsU) -> Option<Ms>,
-            identity,
-            "Streams can stream only Msg, Option<Msg> or ()!",
-            Box
-        );
 
         #[allow(clippy::redundant_closure)]
-        let stream = stream.map(move |msg| app.mailbox().send(handler(msg).map(|msg| f(msg))));
-        StreamManager::stream_with_handle(stream)
+        let stream = stream.map(move |msg| msg.into_option_msg().map(|msg| f(msg)));
+        self.orders_container.stream_with_handle(stream)
+    }
+
+    fn clone_base_path(&self) -> Rc<[String]> {
+        self.orders_container.clone_base_path()
     }
 }
@@ -0,0 +1,147 @@
+use super::App;
+use crate::ssr::nodes_to_string;
+#[cfg(panic = "unwind")]
+use crate::virtual_dom::error_boundary::in_error_boundary;
+use crate::virtual_dom::IntoNodes;
+use std::{
+    cell::RefCell,
+    panic::{self, PanicInfo},
+    rc::Rc,
+    sync::Once,
+};
+use uuid::Uuid;
+
+type Handler = Rc<dyn Fn(&PanicInfo)>;
+
+thread_local! {
+    // Panic handlers of running apps - `panic::set_hook` requires `Send + Sync` hooks.
+    static HANDLERS: RefCell<Vec<(Uuid, Handler)>> = RefCell::new(Vec::new());
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Wrap the current panic hook (e.g. `console_error_panic_hook`) with a hook that calls
+/// the registered handlers (unless the panic will be caught by an `error_boundary`).
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            previous_hook(info);
+            #[cfg(panic = "unwind")]
+            if in_error_boundary() {
+                return;
+            }
+            let handlers = HANDLERS.with(|handlers| {
+                handlers
+                    .try_borrow()
+                    .map(|handlers| handlers.iter().map(|(_, h)| Rc::clone(h)).collect())
+                    .unwrap_or_else(|_| Vec::new())
+            });
+            for handler in handlers {
+                handler(info);
+            }
+        }));
+    });
+}
+
+// ------ PanicHandlerGuard ------
+
+/// Removes the registered panic handler on drop.
+pub(crate) struct PanicHandlerGuard(Uuid);
+
+impl Drop for PanicHandlerGuard {
+    fn drop(&mut self) {
+        HANDLERS.with(|handlers| {
+            if let Ok(mut handlers) = handlers.try_borrow_mut() {
+                handlers.retain(|(id, _)| *id != self.0);
+            }
+        });
+    }
+}
+
+// ------ PanicContext ------
+
+/// Passed to the handler registered by `App::on_panic`.
+pub struct PanicContext<'a, Ms> {
+    /// The panic location and message.
+    pub info: &'a PanicInfo<'a>,
+    /// The last message passed to `update` (the panic may have happened while it was processed).
+    pub last_message: Option<&'a Ms>,
+    mount_point: &'a web_sys::Element,
+}
+
+impl<'a, Ms> PanicContext<'a, Ms> {
+    /// Replace the app content with the static HTML rendered from `view`.
+    ///
+    /// _Note:_ Event handlers aren't attached - the app can't process messages after a panic.
+    /// Use e.g. a link or a `<form>` to let the user reload the page.
+    pub fn render(&self, view: impl IntoNodes<Ms>) {
+        self.mount_point
+            .set_inner_html(&nodes_to_string(&view.into_nodes()));
+    }
+
+    /// The app's root element.
+    pub const fn mount_point(&self) -> &web_sys::Element {
+        self.mount_point
+    }
+}
+
+// ------ App ------
+
+impl<Ms, Mdl, INodes> App<Ms, Mdl, INodes>
+where
+    Ms: Clone + 'static,
+    Mdl: 'static,
+    INodes: IntoNodes<Ms> + 'static,
+{
+    /// Call the `handler` when the app panics - e.g. to show an error screen
+    /// and to report the error instead of leaving the frozen app.
+    ///
+    /// The panic is still passed to the previous panic hook (e.g. `console_error_panic_hook`).
+    /// Panics caught by `error_boundary` (available only on targets that unwind) are ignored.
+    /// It's the way to recover from panics in the browser, where wasm aborts on panic.
+    /// Messages passed to `update` are cloned to provide `PanicContext::last_message`.
+    ///
+    /// _Note:_ Don't access the app from the handler - its state may be borrowed.
+    /// The handler replaces the previous one and it's removed when the app is unmounted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// app.on_panic(|context: PanicContext<Msg>| {
+    ///     report_error(context.info.to_string(), format!("{:?}", context.last_message));
+    ///     context.render(div![
+    ///         "Something went wrong. ",
+    ///         a!["Reload", attrs! {At::Href => ""}],
+    ///     ]);
+    /// });
+    /// ```
+    pub fn on_panic(&self, handler: impl Fn(PanicContext<Ms>) + 'static) {
+        install_hook();
+
+        let last_message = Rc::new(RefCell::new(None));
+        let recorder_last_message = Rc::clone(&last_message);
+        let mount_point = self.cfg.mount_point.clone();
+
+        let id = Uuid::new_v4();
+        let hook_handler: Handler = Rc::new(move |info: &PanicInfo| {
+            let last_message = last_message.try_borrow().ok();
+            handler(PanicContext {
+                info,
+                last_message: last_message.as_ref().and_then(|message| message.as_ref()),
+                mount_point: &mount_point,
+            });
+        });
+        HANDLERS.with(|handlers| handlers.borrow_mut().push((id, hook_handler)));
+
+        self.data
+            .last_message_recorder
+            .replace(Some(Box::new(move |message: &Ms| {
+                recorder_last_message.replace(Some(message.clone()));
+            })));
+        // The old guard removes the previous handler.
+        self.data
+            .panic_handler_guard
+            .replace(Some(PanicHandlerGuard(id)));
+    }
+}
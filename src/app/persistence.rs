@@ -0,0 +1,385 @@
+//! Model persistence - save a serializable projection of the model to the Web Storage
+//! and restore it after the page reload (e.g. a half-filled form).
+//!
+//! Enable the crate feature `serde` and start the app by `App::start_persisted`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Draft {
+//!     title: String,
+//!     body: String,
+//! }
+//!
+//! fn init(url: Url, draft: Option<Draft>, orders: &mut impl Orders<Msg>) -> Model {
+//!     Model {
+//!         draft: draft.unwrap_or_default(),
+//!         ..
+//!     }
+//! }
+//!
+//! #[wasm_bindgen(start)]
+//! pub fn start() {
+//!     let persistence = Persistence::new("draft", |model: &Model| Draft {
+//!         title: model.draft.title.clone(),
+//!         body: model.draft.body.clone(),
+//!     })
+//!     .version(2);
+//!     App::start_persisted("app", persistence, init, update, view);
+//! }
+//! ```
+
+use super::{cmd_manager::CmdManager, App, CmdHandle, GetElement, OrdersContainer};
+use crate::browser::{
+    json,
+    storage::{SessionStorage, Storage},
+    util::window,
+    Url,
+};
+use crate::virtual_dom::IntoNodes;
+use enclose::enclose;
+use futures::future::FutureExt;
+use gloo_timers::future::TimeoutFuture;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{cell::RefCell, fmt, marker::PhantomData, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+/// Default delay between a processed message and saving of the model.
+pub const DEFAULT_THROTTLE_MS: u32 = 500;
+
+type Migration<State> = Box<dyn Fn(u32, &str) -> Option<State>>;
+
+// ------ Persistence ------
+
+/// Configuration of the model persistence - see `App::start_persisted`.
+///
+/// The state is saved together with its version. A state saved with a different version
+/// is passed to the `migrate` function (if set) or discarded.
+pub struct Persistence<Mdl, State, S: Storage = SessionStorage> {
+    key: String,
+    version: u32,
+    throttle_ms: u32,
+    project: Box<dyn Fn(&Mdl) -> State>,
+    migrate: Option<Migration<State>>,
+    storage: PhantomData<S>,
+}
+
+impl<Mdl, State> Persistence<Mdl, State> {
+    /// Save the state created by `project` from the model under the `key` in `SessionStorage`.
+    pub fn new(key: impl Into<String>, project: impl Fn(&Mdl) -> State + 'static) -> Self {
+        Self {
+            key: key.into(),
+            version: 0,
+            throttle_ms: DEFAULT_THROTTLE_MS,
+            project: Box::new(project),
+            migrate: None,
+            storage: PhantomData,
+        }
+    }
+}
+
+impl<Mdl, State, S: Storage> Persistence<Mdl, State, S> {
+    /// Use another storage - e.g. `LocalStorage` to restore the state also in new tabs.
+    pub fn storage<NewS: Storage>(self) -> Persistence<Mdl, State, NewS> {
+        Persistence {
+            key: self.key,
+            version: self.version,
+            throttle_ms: self.throttle_ms,
+            project: self.project,
+            migrate: self.migrate,
+            storage: PhantomData,
+        }
+    }
+
+    /// Set the state schema version (default `0`). Increase it when `State` changes
+    /// so the states saved by the previous app versions aren't deserialized as the new `State`.
+    #[must_use]
+    pub fn version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Save the model at most once per `ms` milliseconds (default `DEFAULT_THROTTLE_MS`).
+    /// The model is also saved when the page is being hidden (e.g. reloaded or closed).
+    #[must_use]
+    pub fn throttle(mut self, ms: u32) -> Self {
+        self.throttle_ms = ms;
+        self
+    }
+
+    /// Convert the state saved with another version. `migrate` receives the saved version
+    /// and the state serialized to JSON; the state is discarded when it returns `None`.
+    #[must_use]
+    pub fn migrate(mut self, migrate: impl Fn(u32, &str) -> Option<State> + 'static) -> Self {
+        self.migrate = Some(Box::new(migrate));
+        self
+    }
+}
+
+impl<Mdl, State: Serialize + DeserializeOwned, S: Storage> Persistence<Mdl, State, S> {
+    /// Load the saved state. An invalid or incompatible state is removed from the storage.
+    pub fn restore(&self) -> Option<State> {
+        let json = S::get_raw(&self.key).ok()?;
+        let state = self.parse(&json);
+        if state.is_none() {
+            self.clear();
+        }
+        state
+    }
+
+    /// Remove the saved state.
+    pub fn clear(&self) {
+        if let Err(error) = S::remove(&self.key) {
+            log_error("Cannot remove persisted state:", &error);
+        }
+    }
+
+    fn parse(&self, json: &str) -> Option<State> {
+        let saved = js_sys::JSON::parse(json).ok()?;
+        let version = js_sys::Reflect::get(&saved, &"version".into())
+            .ok()?
+            .as_f64()?;
+        let state = js_sys::Reflect::get(&saved, &"state".into()).ok()?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let version = version as u32;
+        if version == self.version {
+            return json::from_js_value(&state).ok();
+        }
+        let migrate = self.migrate.as_ref()?;
+        let state_json = js_sys::JSON::stringify(&state).ok()?;
+        migrate(version, &String::from(state_json))
+    }
+
+    fn save(&self, model: &Mdl) {
+        let saved = SavedState {
+            version: self.version,
+            state: &(self.project)(model),
+        };
+        if let Err(error) = S::set(&self.key, &saved) {
+            log_error("Cannot persist state:", &error);
+        }
+    }
+}
+
+impl<Mdl, State, S: Storage> fmt::Debug for Persistence<Mdl, State, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Persistence")
+            .field("key", &self.key)
+            .field("version", &self.version)
+            .field("throttle_ms", &self.throttle_ms)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize)]
+struct SavedState<'a, State> {
+    version: u32,
+    state: &'a State,
+}
+
+fn log_error(message: &str, error: &impl fmt::Debug) {
+    web_sys::console::error_2(&message.into(), &format!("{error:?}").into());
+}
+
+// ------ Persister ------
+
+/// Saves the model of a running `App`.
+pub(crate) struct Persister<Mdl> {
+    save: Box<dyn Fn(&Mdl)>,
+    throttle_ms: u32,
+    scheduled_save: RefCell<Option<CmdHandle>>,
+    // Saves the model when the page is being hidden.
+    pagehide_listener: RefCell<Option<Closure<dyn Fn()>>>,
+}
+
+impl<Mdl> Drop for Persister<Mdl> {
+    fn drop(&mut self) {
+        if let Some(listener) = self.pagehide_listener.take() {
+            window()
+                .remove_event_listener_with_callback("pagehide", listener.as_ref().unchecked_ref())
+                .expect("remove pagehide listener");
+        }
+    }
+}
+
+// ------ App ------
+
+impl<Ms, Mdl, INodes> App<Ms, Mdl, INodes>
+where
+    INodes: IntoNodes<Ms> + 'static,
+{
+    /// Like `App::start`, but the state saved by the `persistence` is restored
+    /// and passed to `init`. The state is saved after processed messages.
+    ///
+    /// See [`seed::app::persistence`](persistence/index.html) for an example.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the root element cannot be found.
+    pub fn start_persisted<State, S>(
+        root_element: impl GetElement,
+        persistence: Persistence<Mdl, State, S>,
+        init: impl FnOnce(Url, Option<State>, &mut OrdersContainer<Ms, Mdl, INodes>) -> Mdl + 'static,
+        update: impl FnOnce(Ms, &mut Mdl, &mut OrdersContainer<Ms, Mdl, INodes>) + Clone + 'static,
+        view: impl FnOnce(&Mdl) -> INodes + Clone + 'static,
+    ) -> Self
+    where
+        State: Serialize + DeserializeOwned + 'static,
+        S: Storage + 'static,
+    {
+        let state = persistence.restore();
+        let app = Self::start(
+            root_element,
+            move |url, orders| init(url, state, orders),
+            update,
+            view,
+        );
+
+        let persister = Rc::new(Persister {
+            throttle_ms: persistence.throttle_ms,
+            save: Box::new(move |model| persistence.save(model)),
+            scheduled_save: RefCell::new(None),
+            pagehide_listener: RefCell::new(None),
+        });
+        let pagehide_listener: Closure<dyn Fn()> =
+            Closure::new(enclose!((app => s) move || s.persist_model()));
+        window()
+            .add_event_listener_with_callback(
+                "pagehide",
+                pagehide_listener.as_ref().unchecked_ref(),
+            )
+            .expect("add pagehide listener");
+        persister.pagehide_listener.replace(Some(pagehide_listener));
+        app.data.persister.replace(Some(persister));
+        app
+    }
+
+    /// Save the model after the throttle delay (if the persistence is enabled and the save
+    /// hasn't been scheduled yet).
+    pub(crate) fn schedule_persistence(&self) {
+        let Some(persister) = self.data.persister.borrow().clone() else {
+            return;
+        };
+        let mut scheduled_save = persister.scheduled_save.borrow_mut();
+        if scheduled_save.is_none() {
+            let cmd = TimeoutFuture::new(persister.throttle_ms)
+                .map(enclose!((self => s) move |_| s.persist_model()));
+            *scheduled_save = Some(CmdManager::perform_cmd_with_handle(cmd));
+        }
+    }
+
+    fn persist_model(&self) {
+        let Some(persister) = self.data.persister.borrow().clone() else {
+            return;
+        };
+        // The finished save cmd can be aborted safely.
+        drop(persister.scheduled_save.take());
+        if let Ok(model) = self.data.model.try_borrow() {
+            if let Some(model) = model.as_ref() {
+                (persister.save)(model);
+            }
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as seed;
+    use crate::browser::storage::LocalStorage;
+    use crate::prelude::*;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Draft {
+        title: String,
+    }
+
+    fn persistence(key: &str) -> Persistence<String, Draft, LocalStorage> {
+        Persistence::new(key, |title: &String| Draft {
+            title: title.clone(),
+        })
+        .storage()
+    }
+
+    #[wasm_bindgen_test]
+    fn restore_checks_version() {
+        let persistence = persistence("seed-test-persistence-version").version(1);
+        LocalStorage::set_raw(
+            "seed-test-persistence-version",
+            r#"{"version": 1, "state": {"title": "Hello"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            persistence.restore(),
+            Some(Draft {
+                title: "Hello".to_owned()
+            })
+        );
+
+        let persistence = persistence.version(2);
+        assert_eq!(persistence.restore(), None);
+        // The incompatible state has been removed.
+        assert!(LocalStorage::get_raw("seed-test-persistence-version").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn restore_migrates_state() {
+        let persistence = persistence("seed-test-persistence-migration")
+            .version(2)
+            .migrate(|version, json| {
+                (version == 1).then(|| Draft {
+                    title: json.to_owned(),
+                })
+            });
+        LocalStorage::set_raw(
+            "seed-test-persistence-migration",
+            r#"{"version": 1, "state": "Hi"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            persistence.restore(),
+            Some(Draft {
+                title: r#""Hi""#.to_owned()
+            })
+        );
+        persistence.clear();
+    }
+
+    #[wasm_bindgen_test]
+    async fn app_restores_and_saves_state() {
+        let key = "seed-test-persistence-app";
+        LocalStorage::set_raw(key, r#"{"version": 0, "state": {"title": "Saved"}}"#).unwrap();
+        let mount_point = seed::document().create_element("div").unwrap();
+
+        let app: App<String, String, Node<String>> = App::start_persisted(
+            mount_point,
+            persistence(key).throttle(0),
+            |_, draft, _| draft.map(|draft| draft.title).unwrap_or_default(),
+            |title, model, _| *model = title,
+            |_| empty![],
+        );
+        assert_eq!(app.data.model.borrow().as_deref(), Some("Saved"));
+
+        app.update("New".to_owned());
+        TimeoutFuture::new(10).await;
+        assert_eq!(
+            LocalStorage::get::<SavedDraft>(key).unwrap().state.title,
+            "New"
+        );
+        app.unmount();
+        LocalStorage::remove(key).unwrap();
+    }
+
+    #[derive(Deserialize)]
+    struct SavedDraft {
+        state: Draft,
+    }
+}
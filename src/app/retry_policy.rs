@@ -0,0 +1,310 @@
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+// ------ Backoff ------
+
+/// How the delay grows with the attempt number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// `initial_ms * base ^ (attempt - 1)`
+    Exponential { initial_ms: u32, base: f64 },
+    /// `initial_ms + step_ms * (attempt - 1)`
+    Linear { initial_ms: u32, step_ms: u32 },
+    /// The same delay for all attempts.
+    Constant(u32),
+}
+
+// ------ Jitter ------
+
+/// Randomization of delays - it prevents clients from retrying at the same time.
+///
+/// See [Exponential Backoff And Jitter](https://aws.amazon.com/blogs/architecture/exponential-backoff-and-jitter/).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jitter {
+    None,
+    /// A random delay between `0` and the computed delay.
+    Full,
+    /// Half of the computed delay plus a random delay between `0` and the other half.
+    Equal,
+    /// The computed delay plus a random delay between `0` and the given number of milliseconds.
+    Additive(u32),
+}
+
+// ------ RetryPolicy ------
+
+/// Delays between attempts of a fallible operation and when to give up.
+///
+/// Used by `cmds::retry`, `streams::backoff_with_policy` and `Retries`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///let policy = RetryPolicy::exponential(500)
+///    .max_delay(10_000)
+///    .jitter(Jitter::Full)
+///    .max_attempts(5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    backoff: Backoff,
+    max_delay: u32,
+    jitter: Jitter,
+    max_attempts: Option<usize>,
+    reset_on_success: bool,
+}
+
+impl Default for RetryPolicy {
+    /// The policy used by `streams::backoff` -
+    /// [Truncated exponential backoff](https://cloud.google.com/storage/docs/exponential-backoff)
+    /// with `1s` initial delay, `32s` max delay and `0 - 1000ms` additive jitter.
+    fn default() -> Self {
+        Self::exponential(1000)
+            .max_delay(32_000)
+            .jitter(Jitter::Additive(1000))
+    }
+}
+
+impl RetryPolicy {
+    /// Delays `initial_ms`, `2 * initial_ms`, `4 * initial_ms`, ... without jitter and limits.
+    ///
+    /// The default base `2` can be changed by `base`.
+    pub const fn exponential(initial_ms: u32) -> Self {
+        Self::new(Backoff::Exponential {
+            initial_ms,
+            base: 2.,
+        })
+    }
+
+    /// Delays `initial_ms`, `initial_ms + step_ms`, `initial_ms + 2 * step_ms`, ...
+    /// without jitter and limits.
+    pub const fn linear(initial_ms: u32, step_ms: u32) -> Self {
+        Self::new(Backoff::Linear {
+            initial_ms,
+            step_ms,
+        })
+    }
+
+    /// The delay `ms` for all attempts, without jitter and limits.
+    pub const fn constant(ms: u32) -> Self {
+        Self::new(Backoff::Constant(ms))
+    }
+
+    const fn new(backoff: Backoff) -> Self {
+        Self {
+            backoff,
+            max_delay: u32::MAX,
+            jitter: Jitter::None,
+            max_attempts: None,
+            reset_on_success: true,
+        }
+    }
+
+    /// Set the exponent base of `exponential` policies. It's ignored by other policies.
+    #[must_use]
+    pub const fn base(mut self, base: f64) -> Self {
+        if let Backoff::Exponential { initial_ms, .. } = self.backoff {
+            self.backoff = Backoff::Exponential { initial_ms, base };
+        }
+        self
+    }
+
+    /// Truncate delays to `ms` milliseconds (jitter included).
+    #[must_use]
+    pub const fn max_delay(mut self, ms: u32) -> Self {
+        self.max_delay = ms;
+        self
+    }
+
+    #[must_use]
+    pub const fn jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Give up after `max_attempts` retries.
+    #[must_use]
+    pub const fn max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Whether `Retries::succeeded` resets the attempt counter (default `true`).
+    ///
+    /// Set it to `false` to limit the total number of attempts by `max_attempts`
+    /// and to keep increasing delays, e.g. for a connection that drops right after it's established.
+    #[must_use]
+    pub const fn reset_on_success(mut self, reset_on_success: bool) -> Self {
+        self.reset_on_success = reset_on_success;
+        self
+    }
+
+    /// The delay in milliseconds before the retry `attempt` (starting from `1`)
+    /// or `None` if the policy gives up.
+    pub fn delay(&self, attempt: usize) -> Option<u32> {
+        self.delay_with_rng(attempt, &mut SmallRng::from_entropy())
+    }
+
+    fn delay_with_rng(&self, attempt: usize, rng: &mut impl Rng) -> Option<u32> {
+        if attempt == 0 || self.max_attempts.map_or(false, |max| attempt > max) {
+            return None;
+        }
+        let exponent = u32::try_from(attempt - 1).unwrap_or(u32::MAX);
+
+        let delay = match self.backoff {
+            Backoff::Exponential { initial_ms, base } => {
+                saturating_u32(f64::from(initial_ms) * base.powf(f64::from(exponent)))
+            }
+            Backoff::Linear {
+                initial_ms,
+                step_ms,
+            } => initial_ms.saturating_add(step_ms.saturating_mul(exponent)),
+            Backoff::Constant(ms) => ms,
+        };
+        let delay = delay.min(self.max_delay);
+
+        let delay = match self.jitter {
+            Jitter::None => delay,
+            Jitter::Full => rng.gen_range(0..=delay),
+            Jitter::Equal => delay / 2 + rng.gen_range(0..=delay - delay / 2),
+            Jitter::Additive(ms) => delay
+                .saturating_add(rng.gen_range(0..=ms))
+                .min(self.max_delay),
+        };
+        Some(delay)
+    }
+}
+
+// Float to int `as` casts saturate (and `NaN` is cast to `0`).
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn saturating_u32(value: f64) -> u32 {
+    value as u32
+}
+
+// ------ Retries ------
+
+/// Attempt counter for long-lived retried operations like websocket reconnections.
+///
+/// # Example
+///
+/// ```rust,ignore
+///Msg::WebSocketClosed => {
+///    if let Some(delay) = model.reconnections.next_delay() {
+///        orders.perform_cmd(cmds::timeout(delay, || Msg::Reconnect));
+///    }
+///}
+///Msg::WebSocketOpened => {
+///    model.reconnections.succeeded();
+///}
+/// ```
+#[derive(Debug, Clone)]
+pub struct Retries {
+    policy: RetryPolicy,
+    attempts: usize,
+}
+
+impl Retries {
+    pub const fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            attempts: 0,
+        }
+    }
+
+    /// Count a new attempt and get the delay before it or `None` if the policy gives up.
+    pub fn next_delay(&mut self) -> Option<u32> {
+        self.attempts = self.attempts.saturating_add(1);
+        self.policy.delay(self.attempts)
+    }
+
+    /// The number of attempts counted by `next_delay`.
+    pub const fn attempts(&self) -> usize {
+        self.attempts
+    }
+
+    /// Report a successful attempt - it resets the counter if the policy's `reset_on_success` is `true`.
+    pub fn succeeded(&mut self) {
+        if self.policy.reset_on_success {
+            self.reset();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+    }
+
+    pub const fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delays(policy: &RetryPolicy, attempts: usize) -> Vec<Option<u32>> {
+        let mut rng = SmallRng::seed_from_u64(0);
+        (1..=attempts)
+            .map(|attempt| policy.delay_with_rng(attempt, &mut rng))
+            .collect()
+    }
+
+    #[test]
+    fn backoff_kinds() {
+        let exponential = RetryPolicy::exponential(100).max_delay(1000);
+        assert_eq!(
+            delays(&exponential, 5),
+            [Some(100), Some(200), Some(400), Some(800), Some(1000)]
+        );
+
+        let base_3 = RetryPolicy::exponential(100).base(3.);
+        assert_eq!(delays(&base_3, 3), [Some(100), Some(300), Some(900)]);
+
+        let linear = RetryPolicy::linear(100, 50);
+        assert_eq!(delays(&linear, 3), [Some(100), Some(150), Some(200)]);
+
+        let constant = RetryPolicy::constant(250);
+        assert_eq!(delays(&constant, 2), [Some(250), Some(250)]);
+    }
+
+    #[test]
+    fn max_attempts() {
+        let policy = RetryPolicy::constant(10).max_attempts(2);
+        assert_eq!(delays(&policy, 3), [Some(10), Some(10), None]);
+    }
+
+    #[test]
+    fn jitter_bounds() {
+        let full = RetryPolicy::constant(1000).jitter(Jitter::Full);
+        assert!(delays(&full, 100).into_iter().all(|d| d.unwrap() <= 1000));
+
+        let equal = RetryPolicy::constant(1000).jitter(Jitter::Equal);
+        assert!(delays(&equal, 100)
+            .into_iter()
+            .all(|d| (500..=1000).contains(&d.unwrap())));
+
+        let additive = RetryPolicy::constant(1000)
+            .max_delay(1500)
+            .jitter(Jitter::Additive(1000));
+        assert!(delays(&additive, 100)
+            .into_iter()
+            .all(|d| (1000..=1500).contains(&d.unwrap())));
+    }
+
+    #[test]
+    fn retries_reset_on_success() {
+        let mut retries = Retries::new(RetryPolicy::exponential(100).max_attempts(2));
+        assert_eq!(retries.next_delay(), Some(100));
+        assert_eq!(retries.next_delay(), Some(200));
+        retries.succeeded();
+        assert_eq!(retries.next_delay(), Some(100));
+
+        let mut retries = Retries::new(
+            RetryPolicy::exponential(100)
+                .max_attempts(2)
+                .reset_on_success(false),
+        );
+        retries.next_delay();
+        retries.succeeded();
+        assert_eq!(retries.next_delay(), Some(200));
+        assert_eq!(retries.next_delay(), None);
+    }
+}
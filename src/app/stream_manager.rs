@@ -1,15 +1,33 @@
 use futures::future::{abortable, ready, AbortHandle, FutureExt};
 use futures::stream::{Stream, StreamExt};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use uuid::Uuid;
 use wasm_bindgen_futures::spawn_local;
 
 // ------ StreamManager ------
 
-pub(crate) struct StreamManager;
+#[derive(Default)]
+pub(crate) struct StreamManager {
+    // Handles of the running streams started without a handle.
+    // They are aborted when the `App` is unmounted.
+    handles: Rc<RefCell<HashMap<Uuid, StreamHandle>>>,
+}
 
 impl StreamManager {
-    pub fn stream(stream: impl Stream<Item = ()> + 'static) {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stream(&self, stream: impl Stream<Item = ()> + 'static) {
+        let id = Uuid::new_v4();
+        let handles = Rc::clone(&self.handles);
         // Convert `Stream` to `Future` and execute it. The stream is "leaked" into the JS world.
-        spawn_local(stream.for_each(|_| ready(())));
+        let stream = stream.for_each(|_| ready(())).map(move |()| {
+            handles.borrow_mut().remove(&id);
+        });
+        let (stream, handle) = abortable(stream);
+        spawn_local(stream.map(move |_| ()));
+        self.handles.borrow_mut().insert(id, StreamHandle(handle));
     }
 
     pub fn stream_with_handle(stream: impl Stream<Item = ()> + 'static) -> StreamHandle {
@@ -21,12 +39,18 @@ impl StreamManager {
         spawn_local(stream.map(move |_| ()));
         StreamHandle(handle)
     }
+
+    /// Abort all running streams started without a handle.
+    pub fn abort_all(&self) {
+        let handles = std::mem::take(&mut *self.handles.borrow_mut());
+        drop(handles);
+    }
 }
 
 // ------ StreamHandle ------
 
 #[derive(Debug)]
-pub struct StreamHandle(AbortHandle);
+pub struct StreamHandle(pub(crate) AbortHandle);
 
 impl Drop for StreamHandle {
     fn drop(&mut self) {
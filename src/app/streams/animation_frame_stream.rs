@@ -0,0 +1,119 @@
+use super::event_stream::EventStream;
+use crate::app::RenderInfo;
+use crate::browser::util::{document, request_animation_frame, RequestAnimationFrameHandle};
+use crate::virtual_dom::Ev;
+use futures::stream::{Stream, StreamExt};
+use std::cell::{Cell, RefCell};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use wasm_bindgen::closure::Closure;
+
+// ------ AnimationFrameStream ------
+
+/// Emits timestamps of animation frames.
+///
+/// A frame is requested only when the stream is polled and no frames are requested
+/// while the document is hidden. The first frame and the first frame after the document
+/// becomes visible again don't have `timestamp_delta`.
+#[derive(Debug)]
+pub struct AnimationFrameStream {
+    previous_timestamp: Option<f64>,
+    frame: Option<Frame>,
+    visibility_changes: EventStream<web_sys::Event>,
+}
+
+impl AnimationFrameStream {
+    pub fn new() -> Self {
+        Self {
+            previous_timestamp: None,
+            frame: None,
+            visibility_changes: EventStream::new(&document(), Ev::VisibilityChange),
+        }
+    }
+}
+
+impl Default for AnimationFrameStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stream for AnimationFrameStream {
+    type Item = RenderInfo;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        // Register the waker for visibility changes; the events themselves aren't needed.
+        while let Poll::Ready(Some(_)) = self.visibility_changes.poll_next_unpin(cx) {}
+
+        if document().hidden() {
+            // The frame request is cancelled on drop.
+            self.frame = None;
+            self.previous_timestamp = None;
+            return Poll::Pending;
+        }
+
+        let Some(frame) = self.frame.as_ref() else {
+            self.frame = Some(Frame::request(cx.waker().clone()));
+            return Poll::Pending;
+        };
+        let Some(timestamp) = frame.state.timestamp.get() else {
+            frame.state.waker.replace(Some(cx.waker().clone()));
+            return Poll::Pending;
+        };
+        self.frame = None;
+
+        let timestamp_delta = self
+            .previous_timestamp
+            .map(|previous_timestamp| timestamp - previous_timestamp);
+        self.previous_timestamp = Some(timestamp);
+        Poll::Ready(Some(RenderInfo {
+            timestamp,
+            timestamp_delta,
+        }))
+    }
+}
+
+// ------ Frame ------
+
+#[derive(Debug)]
+struct FrameState {
+    timestamp: Cell<Option<f64>>,
+    waker: RefCell<Option<Waker>>,
+}
+
+/// Requested animation frame. The request is cancelled on drop.
+struct Frame {
+    state: Rc<FrameState>,
+    _handle: RequestAnimationFrameHandle,
+}
+
+impl Frame {
+    fn request(waker: Waker) -> Self {
+        let state = Rc::new(FrameState {
+            timestamp: Cell::new(None),
+            waker: RefCell::new(Some(waker)),
+        });
+        let callback = Closure::wrap(Box::new({
+            let state = Rc::clone(&state);
+            move |timestamp: f64| {
+                state.timestamp.set(Some(timestamp));
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        }) as Box<dyn FnMut(f64)>);
+        Self {
+            state,
+            _handle: request_animation_frame(callback),
+        }
+    }
+}
+
+impl std::fmt::Debug for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Frame")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,35 +1,28 @@
-use futures::channel::mpsc;
+use crate::app::RetryPolicy;
+use futures::future::FutureExt;
 use futures::stream::Stream;
-use gloo_timers::callback::Timeout;
-use rand::{rngs::SmallRng, Rng, SeedableRng};
+use gloo_timers::future::TimeoutFuture;
 use std::pin::Pin;
-use std::rc::Rc;
 use std::task::{Context, Poll};
 
 // ------ BackoffStream ------
 
-/// [Truncated exponential backoff](https://cloud.google.com/storage/docs/exponential-backoff)
+/// Emits retry numbers after delays given by the `RetryPolicy`.
+/// It ends when the policy gives up.
 #[derive(Debug)]
 pub struct BackoffStream {
-    max_seconds: u32,
+    policy: RetryPolicy,
     retries: usize,
-    timeout: Timeout,
-    tick_sender: Rc<mpsc::UnboundedSender<()>>,
-    tick_receiver: mpsc::UnboundedReceiver<()>,
+    timeout: Option<TimeoutFuture>,
 }
 
 impl BackoffStream {
-    pub fn new(max_seconds: u32) -> Self {
-        let (tick_sender, tick_receiver) = mpsc::unbounded();
-        let tick_sender = Rc::new(tick_sender);
-
+    pub fn new(policy: RetryPolicy) -> Self {
         let retries = 0;
         Self {
-            max_seconds,
+            timeout: start_timeout(&policy, retries),
+            policy,
             retries,
-            timeout: start_timeout(wait_time(retries, max_seconds), &tick_sender),
-            tick_sender,
-            tick_receiver,
         }
     }
 }
@@ -38,35 +31,20 @@ impl Stream for BackoffStream {
     type Item = usize;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
-        match Stream::poll_next(Pin::new(&mut self.tick_receiver), cx) {
-            Poll::Ready(Some(_)) => {
+        let Some(timeout) = self.timeout.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match timeout.poll_unpin(cx) {
+            Poll::Ready(()) => {
                 self.retries += 1;
-                self.timeout =
-                    start_timeout(wait_time(self.retries, self.max_seconds), &self.tick_sender);
+                self.timeout = start_timeout(&self.policy, self.retries);
                 Poll::Ready(Some(self.retries))
             }
-            Poll::Ready(None) => Poll::Ready(None),
             Poll::Pending => Poll::Pending,
         }
     }
 }
 
-fn wait_time(retries: usize, max_seconds: u32) -> u32 {
-    let retries = u32::try_from(retries).unwrap_or(u32::max_value());
-    let random_ms = SmallRng::from_entropy().gen_range(0..=1000);
-
-    let duration = 2_u32
-        .saturating_pow(retries)
-        .saturating_mul(1000)
-        .saturating_add(random_ms);
-    let max_duration = max_seconds.saturating_mul(1000);
-
-    u32::min(duration, max_duration)
-}
-
-fn start_timeout(ms: u32, tick_sender: &Rc<mpsc::UnboundedSender<()>>) -> Timeout {
-    let tick_sender = Rc::clone(tick_sender);
-    Timeout::new(ms, move || {
-        tick_sender.unbounded_send(()).expect("send backoff tick");
-    })
+fn start_timeout(policy: &RetryPolicy, retries: usize) -> Option<TimeoutFuture> {
+    policy.delay(retries + 1).map(TimeoutFuture::new)
 }
@@ -0,0 +1,75 @@
+use super::event_stream::EventStream;
+use crate::browser::json;
+use crate::virtual_dom::Ev;
+use futures::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use web_sys::{BroadcastChannel, MessageEvent};
+
+thread_local! {
+    // Channels are shared by streams and `cmds::broadcast` so that the tab
+    // doesn't receive messages it has sent itself.
+    static CHANNELS: RefCell<HashMap<String, BroadcastChannel>> = RefCell::new(HashMap::new());
+}
+
+/// Get the tab's channel with the `name`; it's created on the first call.
+///
+/// # Panics
+///
+/// Panics when the browser doesn't support `BroadcastChannel`.
+pub(crate) fn channel(name: &str) -> BroadcastChannel {
+    CHANNELS.with(|channels| {
+        channels
+            .borrow_mut()
+            .entry(name.to_owned())
+            .or_insert_with(|| BroadcastChannel::new(name).expect("create broadcast channel"))
+            .clone()
+    })
+}
+
+// ------ BroadcastChannelStream ------
+
+/// Emits messages posted to the `BroadcastChannel` by other tabs, deserialized to `T`.
+///
+/// Messages that can't be deserialized are logged to the console and skipped.
+/// The listener is removed on drop.
+#[derive(Debug)]
+pub struct BroadcastChannelStream<T> {
+    events: EventStream<MessageEvent>,
+    _message: PhantomData<T>,
+}
+
+impl<T> BroadcastChannelStream<T> {
+    pub fn new(name: &str) -> Self {
+        Self {
+            events: EventStream::new(&channel(name), Ev::Message),
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<T> Unpin for BroadcastChannelStream<T> {}
+
+impl<T: DeserializeOwned> Stream for BroadcastChannelStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        while let Poll::Ready(event) = self.events.poll_next_unpin(cx) {
+            let Some(event) = event else {
+                return Poll::Ready(None);
+            };
+            match json::from_js_value(&event.data()) {
+                Ok(message) => return Poll::Ready(Some(message)),
+                Err(error) => web_sys::console::error_2(
+                    &"Cannot deserialize broadcast channel message:".into(),
+                    &error,
+                ),
+            }
+        }
+        Poll::Pending
+    }
+}
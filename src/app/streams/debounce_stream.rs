@@ -0,0 +1,68 @@
+use futures::future::FutureExt;
+use futures::stream::{LocalBoxStream, Stream, StreamExt};
+use gloo_timers::future::TimeoutFuture;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ------ DebounceStream ------
+
+/// Emits the latest item of the inner stream once the inner stream has been quiet for `ms` milliseconds.
+///
+/// The pending item is emitted immediately when the inner stream ends.
+pub struct DebounceStream<T> {
+    ms: u32,
+    stream: Option<LocalBoxStream<'static, T>>,
+    pending: Option<T>,
+    timeout: Option<TimeoutFuture>,
+}
+
+impl<T> DebounceStream<T> {
+    pub fn new(ms: u32, stream: impl Stream<Item = T> + 'static) -> Self {
+        Self {
+            ms,
+            stream: Some(stream.boxed_local()),
+            pending: None,
+            timeout: None,
+        }
+    }
+}
+
+// `TimeoutFuture` and `LocalBoxStream` are `Unpin`, but `T` may not be.
+impl<T> Unpin for DebounceStream<T> {}
+
+impl<T> Stream for DebounceStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        while let Some(stream) = self.stream.as_mut() {
+            match stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => {
+                    self.pending = Some(item);
+                    // The previous timeout is cancelled on drop.
+                    self.timeout = Some(TimeoutFuture::new(self.ms));
+                }
+                Poll::Ready(None) => {
+                    self.stream = None;
+                    self.timeout = None;
+                    return Poll::Ready(self.pending.take());
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        let Some(timeout) = self.timeout.as_mut() else {
+            return if self.stream.is_some() {
+                Poll::Pending
+            } else {
+                Poll::Ready(None)
+            };
+        };
+        match timeout.poll_unpin(cx) {
+            Poll::Ready(()) => {
+                self.timeout = None;
+                Poll::Ready(self.pending.take())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -1,15 +1,31 @@
+use crate::app::{RenderInfo, RetryPolicy};
 use crate::browser::util::{document, window};
 use crate::virtual_dom::Ev;
-use futures::stream::{Stream, StreamExt};
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
 use gloo_timers::future::IntervalStream;
 use web_sys::Event;
 
 mod event_stream;
 use event_stream::EventStream;
 
+mod animation_frame_stream;
+pub use animation_frame_stream::AnimationFrameStream;
+
+#[cfg(feature = "serde")]
+pub(crate) mod broadcast_channel_stream;
+#[cfg(feature = "serde")]
+pub use broadcast_channel_stream::BroadcastChannelStream;
+
 mod backoff_stream;
 use backoff_stream::BackoffStream;
 
+mod debounce_stream;
+pub use debounce_stream::DebounceStream;
+
+mod throttle_stream;
+pub use throttle_stream::ThrottleStream;
+
 // ------ Interval stream ------
 
 /// Stream no values on predefined time interval in milliseconds.
@@ -22,11 +38,6 @@ use backoff_stream::BackoffStream;
 ///orders.stream(streams::interval(1000, || Msg::OnTick));
 ///orders.stream_with_handle(streams::interval(1000, || log!("Tick!")));
 /// ```
-///
-/// # Panics
-///
-/// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-/// (It will be changed to a compile-time error).
 pub fn interval<MsU>(
     ms: u32,
     handler: impl FnOnce() -> MsU + Clone + 'static,
@@ -51,16 +62,181 @@ pub fn interval<MsU>(
 ///orders.stream(streams::backoff(None, |_retries| Msg::OnTick));
 ///orders.stream_with_handle(streams::backoff(Some(15), |_| log!("Tick!")));
 /// ```
-///
-/// # Panics
-///
-/// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-/// (It will be changed to a compile-time error).
 pub fn backoff<MsU>(
     max_seconds: Option<u32>,
     handler: impl FnOnce(usize) -> MsU + Clone + 'static,
 ) -> impl Stream<Item = MsU> {
-    BackoffStream::new(max_seconds.unwrap_or(32)).map(move |retries| handler.clone()(retries))
+    let policy = RetryPolicy::default().max_delay(max_seconds.unwrap_or(32).saturating_mul(1000));
+    backoff_with_policy(policy, handler)
+}
+
+/// Stream retries count in intervals given by the `policy`.
+/// The stream ends when the policy gives up (see `RetryPolicy::max_attempts`).
+///
+/// Handler receives the number of retries (starting from 1); Has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///let policy = RetryPolicy::linear(1000, 500).jitter(Jitter::Equal).max_attempts(10);
+///orders.stream(streams::backoff_with_policy(policy, Msg::Reconnect));
+/// ```
+pub fn backoff_with_policy<MsU>(
+    policy: RetryPolicy,
+    handler: impl FnOnce(usize) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    BackoffStream::new(policy).map(move |retries| handler.clone()(retries))
+}
+
+// ------ Animation frame stream ------
+
+/// Stream animation frames - `RenderInfo` contains the frame timestamp and the time elapsed
+/// since the previous frame.
+///
+/// Frames aren't requested while the document is hidden; the first frame after the document
+/// becomes visible again has `timestamp_delta` `None` (like the first frame).
+///
+/// Handler has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::animation_frame(Msg::OnAnimationFrame));
+///model.animation = Some(orders.stream_with_handle(streams::animation_frame(Msg::OnAnimationFrame)));
+/// ```
+pub fn animation_frame<MsU>(
+    handler: impl FnOnce(RenderInfo) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    AnimationFrameStream::new().map(move |frame| handler.clone()(frame))
+}
+
+// ------ Fixed timestep stream ------
+
+/// The longest frame duration (in milliseconds) counted by `fixed_timestep`.
+/// It prevents a burst of ticks after a long frame (e.g. caused by a debugger breakpoint).
+pub const MAX_FIXED_TIMESTEP_FRAME_MS: f64 = 250.;
+
+/// Tick emitted by `fixed_timestep`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FixedTick {
+    /// Number of the tick, starting from `1`.
+    pub index: u64,
+    /// Tick duration in milliseconds - `1000 / hz`.
+    pub step: f64,
+}
+
+/// Stream `hz` ticks per second on animation frames - a deterministic game loop.
+///
+/// The frame durations are accumulated and each frame emits as many ticks as fit into
+/// the accumulated time - i.e. zero, one or more ticks per frame.
+/// Frame durations are limited to `MAX_FIXED_TIMESTEP_FRAME_MS` and the time
+/// while the document is hidden isn't counted.
+///
+/// Handler has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::fixed_timestep(60, |tick| Msg::Step(tick.step)));
+/// ```
+pub fn fixed_timestep<MsU>(
+    hz: u32,
+    handler: impl FnOnce(FixedTick) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    let step = 1000. / f64::from(hz.max(1));
+    let mut accumulator = 0.;
+    let mut index = 0;
+    AnimationFrameStream::new().flat_map(move |frame| {
+        accumulator += frame
+            .timestamp_delta
+            .unwrap_or_default()
+            .min(MAX_FIXED_TIMESTEP_FRAME_MS);
+        let mut ticks = Vec::new();
+        while accumulator >= step {
+            accumulator -= step;
+            index += 1;
+            ticks.push(handler.clone()(FixedTick { index, step }));
+        }
+        stream::iter(ticks)
+    })
+}
+
+// ------ Media query stream ------
+
+/// Stream whether the document matches the media `query`.
+/// The current value is emitted immediately, then every change.
+///
+/// Handler has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::media_query("(max-width: 600px)", Msg::MobileLayout));
+/// ```
+///
+/// # Panics
+///
+/// Panics when the `query` can't be parsed.
+pub fn media_query<MsU>(
+    query: &str,
+    handler: impl FnOnce(bool) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    let media_query_list = window()
+        .match_media(query)
+        .ok()
+        .flatten()
+        .expect("match media query");
+    let changes = EventStream::<web_sys::MediaQueryListEvent>::new(&media_query_list, Ev::Change)
+        .map(|event| event.matches());
+
+    stream::once(future::ready(media_query_list.matches()))
+        .chain(changes)
+        .map(move |matches| handler.clone()(matches))
+}
+
+/// User's preferred color scheme - see `streams::color_scheme`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+/// Stream the user's preferred color scheme (`prefers-color-scheme`).
+/// The current value is emitted immediately, then every change.
+///
+/// Handler has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::color_scheme(Msg::ColorSchemeChanged));
+/// ```
+pub fn color_scheme<MsU>(
+    handler: impl FnOnce(ColorScheme) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    media_query("(prefers-color-scheme: dark)", move |dark| {
+        handler(if dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        })
+    })
+}
+
+/// Stream whether the user prefers reduced motion (`prefers-reduced-motion: reduce`).
+/// The current value is emitted immediately, then every change.
+///
+/// Handler has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::reduced_motion(Msg::ReducedMotionChanged));
+/// ```
+pub fn reduced_motion<MsU>(
+    handler: impl FnOnce(bool) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    media_query("(prefers-reduced-motion: reduce)", handler)
 }
 
 // ------ Window Event stream ------
@@ -75,11 +251,6 @@ pub fn backoff<MsU>(
 ///orders.stream(streams::window_event(Ev::Resize, |_| Msg::OnResize));
 ///orders.stream_with_handle(streams::window_event(Ev::Click, |_| log!("Clicked!")));
 /// ```
-///
-/// # Panics
-///
-/// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-/// (It will be changed to a compile-time error).
 pub fn window_event<MsU>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(Event) -> MsU + Clone + 'static,
@@ -87,6 +258,61 @@ pub fn window_event<MsU>(
     EventStream::new(&window(), trigger.into()).map(move |event| handler.clone()(event))
 }
 
+// ------ Broadcast channel stream ------
+
+/// Stream messages sent by `cmds::broadcast` with the same channel `name` from other tabs
+/// (and other browsing contexts of the same origin).
+///
+/// Messages are deserialized to `T`; those that can't be deserialized are logged and skipped.
+/// The tab doesn't receive its own messages.
+///
+/// Handler has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///#[derive(Serialize, Deserialize)]
+///enum AuthMessage { LoggedOut }
+///
+///orders.stream(streams::broadcast_channel("auth", |AuthMessage::LoggedOut| Msg::LoggedOut));
+/// ```
+///
+/// # Panics
+///
+/// Panics when the browser doesn't support `BroadcastChannel`.
+#[cfg(feature = "serde")]
+pub fn broadcast_channel<T: serde::de::DeserializeOwned, MsU>(
+    name: &str,
+    handler: impl FnOnce(T) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    BroadcastChannelStream::new(name).map(move |message| handler.clone()(message))
+}
+
+// ------ Storage changes stream ------
+
+/// Stream new values of the `key` in `LocalStorage` changed by other tabs.
+///
+/// Handler receives `None` when the item has been removed or the storage cleared;
+/// Has to return `Msg`, `Option<Msg>` or `()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::storage_changes("cart", Msg::CartChanged));
+/// ```
+pub fn storage_changes<MsU>(
+    key: impl Into<String>,
+    handler: impl FnOnce(Option<String>) -> MsU + Clone + 'static,
+) -> impl Stream<Item = MsU> {
+    let key = key.into();
+    EventStream::<web_sys::StorageEvent>::new(&window(), Ev::Storage).filter_map(move |event| {
+        let from_local_storage = event.storage_area() == window().local_storage().ok().flatten();
+        // `key` is `None` when the storage has been cleared.
+        let changed = from_local_storage && event.key().map_or(true, |event_key| event_key == key);
+        future::ready(changed.then(|| handler.clone()(event.new_value())))
+    })
+}
+
 // ------ Document Event stream ------
 
 /// Stream `Document` `web_sys::Event`s.
@@ -99,14 +325,98 @@ pub fn window_event<MsU>(
 ///orders.stream(streams::document_event(Ev::SelectionChange, |_| Msg::OnSelection));
 ///orders.stream_with_handle(streams::document_event(Ev::SelectionChange, |_| log!("Selection changed!")));
 /// ```
-///
-/// # Panics
-///
-/// Panics when the handler doesn't return `Msg`, `Option<Msg>` or `()`.
-/// (It will be changed to a compile-time error).
 pub fn document_event<MsU>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(Event) -> MsU + Clone + 'static,
 ) -> impl Stream<Item = MsU> {
     EventStream::new(&document(), trigger.into()).map(move |event| handler.clone()(event))
 }
+
+// ------ Debounce stream ------
+
+/// Emit the latest item of the `stream` once the `stream` hasn't produced any items
+/// for `ms` milliseconds.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::debounce(300, streams::window_event(Ev::Resize, |_| Msg::OnResize)));
+/// ```
+pub fn debounce<T>(ms: u32, stream: impl Stream<Item = T> + 'static) -> DebounceStream<T> {
+    DebounceStream::new(ms, stream)
+}
+
+// ------ Throttle stream ------
+
+/// Emit at most one item of the `stream` per `ms` milliseconds.
+///
+/// The first item is emitted immediately, the latest item received in the next `ms` milliseconds
+/// is emitted when they elapse.
+///
+/// # Example
+///
+/// ```rust,ignore
+///orders.stream(streams::throttle(100, streams::document_event(Ev::Scroll, |_| Msg::OnScroll)));
+/// ```
+pub fn throttle<T>(ms: u32, stream: impl Stream<Item = T> + 'static) -> ThrottleStream<T> {
+    ThrottleStream::new(ms, stream)
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gloo_timers::future::TimeoutFuture;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Emit `item`s after the given delays in milliseconds.
+    fn delayed(items: Vec<(u32, i32)>) -> impl Stream<Item = i32> {
+        stream::iter(items).then(|(delay, item)| async move {
+            TimeoutFuture::new(delay).await;
+            item
+        })
+    }
+
+    #[wasm_bindgen_test]
+    async fn debounce_emits_latest_items() {
+        let items = debounce(20, delayed(vec![(0, 1), (5, 2), (60, 3)]));
+        assert_eq!(items.collect::<Vec<_>>().await, vec![2, 3]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn throttle_emits_first_and_latest_items() {
+        let items = throttle(20, delayed(vec![(0, 1), (5, 2), (5, 3), (60, 4)]));
+        assert_eq!(items.collect::<Vec<_>>().await, vec![1, 3, 4]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn backoff_with_policy_ends_when_policy_gives_up() {
+        let policy = RetryPolicy::constant(5).max_attempts(3);
+        let retries = backoff_with_policy(policy, |retries| retries);
+        assert_eq!(retries.collect::<Vec<_>>().await, vec![1, 2, 3]);
+    }
+
+    #[wasm_bindgen_test]
+    async fn animation_frame_emits_deltas() {
+        let frames = animation_frame(|frame| frame)
+            .take(3)
+            .collect::<Vec<_>>()
+            .await;
+        assert!(frames[0].timestamp_delta.is_none());
+        assert!(frames[1..]
+            .iter()
+            .all(|frame| frame.timestamp_delta.is_some()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn media_query_emits_current_value() {
+        let mut matches = media_query("(min-width: 0px)", |matches| matches);
+        assert_eq!(matches.next().await, Some(true));
+
+        let mut matches = media_query("not all", |matches| matches);
+        assert_eq!(matches.next().await, Some(false));
+    }
+}
@@ -0,0 +1,69 @@
+use futures::future::FutureExt;
+use futures::stream::{LocalBoxStream, Stream, StreamExt};
+use gloo_timers::future::TimeoutFuture;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// ------ ThrottleStream ------
+
+/// Emits at most one item per `ms` milliseconds.
+///
+/// The first item is emitted immediately and starts the time window. The latest item received
+/// during the window is emitted when the window ends and it starts a new window.
+pub struct ThrottleStream<T> {
+    ms: u32,
+    stream: Option<LocalBoxStream<'static, T>>,
+    pending: Option<T>,
+    window: Option<TimeoutFuture>,
+}
+
+impl<T> ThrottleStream<T> {
+    pub fn new(ms: u32, stream: impl Stream<Item = T> + 'static) -> Self {
+        Self {
+            ms,
+            stream: Some(stream.boxed_local()),
+            pending: None,
+            window: None,
+        }
+    }
+}
+
+// `TimeoutFuture` and `LocalBoxStream` are `Unpin`, but `T` may not be.
+impl<T> Unpin for ThrottleStream<T> {}
+
+impl<T> Stream for ThrottleStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        while let Some(stream) = self.stream.as_mut() {
+            match stream.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) if self.window.is_none() => {
+                    self.window = Some(TimeoutFuture::new(self.ms));
+                    return Poll::Ready(Some(item));
+                }
+                Poll::Ready(Some(item)) => self.pending = Some(item),
+                Poll::Ready(None) => self.stream = None,
+                Poll::Pending => break,
+            }
+        }
+
+        if let Some(window) = self.window.as_mut() {
+            match window.poll_unpin(cx) {
+                Poll::Ready(()) => {
+                    self.window = None;
+                    if let Some(item) = self.pending.take() {
+                        self.window = Some(TimeoutFuture::new(self.ms));
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        if self.stream.is_some() {
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+}
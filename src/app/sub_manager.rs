@@ -65,15 +65,22 @@ impl<Ms: 'static> SubManager<Ms> {
         let subs = Rc::clone(&self.subs);
         SubHandle {
             unsubscriber: Box::new(move || {
-                subs.borrow_mut()
+                // Subscriptions may have been already removed by `unsubscribe_all`.
+                let removed_sub = subs
+                    .borrow_mut()
                     .get_mut(&type_id)
-                    .expect("get subscriptions by `type_id`")
-                    .remove(&id)
-                    .expect("remove subscription");
+                    .and_then(|subs_group| subs_group.remove(&id));
+                drop(removed_sub);
             }),
         }
     }
 
+    /// Remove all subscriptions, including the ones with `SubHandle`s.
+    pub fn unsubscribe_all(&self) {
+        let subs = std::mem::take(&mut *self.subs.borrow_mut());
+        drop(subs);
+    }
+
     pub fn notify(&self, notification: &Notification) -> Vec<Box<dyn FnOnce() -> Option<Ms>>> {
         self.subs
             .borrow()
@@ -181,4 +188,9 @@ impl Notification {
             message: Rc::new(message),
         }
     }
+
+    /// Returns the message if it's of type `SubMs`.
+    pub fn downcast_ref<SubMs: 'static>(&self) -> Option<&SubMs> {
+        self.message.downcast_ref()
+    }
 }
@@ -5,6 +5,11 @@ use crate::browser::Url;
 pub mod url_requested;
 pub use url_requested::UrlRequested;
 
+// ------ NavigationRequested sub ------
+
+pub mod navigation_requested;
+pub use navigation_requested::{Navigation, NavigationKind, NavigationRequested};
+
 // ------ UrlChanged sub ------
 
 /// Subscribe to url changes.
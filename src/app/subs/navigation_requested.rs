@@ -0,0 +1,158 @@
+use crate::browser::Url;
+use std::{cell::RefCell, mem, rc::Rc};
+
+// ------ NavigationRequested sub ------
+
+/// Fired before Seed changes the url - see `Orders::guard_navigation`.
+#[derive(Debug, Clone)]
+pub struct NavigationRequested(pub Navigation);
+
+// --- NavigationKind ---
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NavigationKind {
+    /// An intercepted `<a>` link click.
+    Link,
+    /// `Orders::request_url` or `subs::UrlRequested` notification.
+    Request,
+    /// The browser back / forward button (or a changed hash in `RoutingMode::Hash`).
+    /// The browser has already changed the url - a blocked navigation re-pushes the previous url.
+    BackForward,
+    /// The page is about to be unloaded (`beforeunload`) - e.g. an external link or a page reload.
+    /// It can't be deferred or redirected - both block the navigation and the browser asks
+    /// the user by its own dialog. `Navigation::url` is the current url.
+    Unload,
+}
+
+// --- Decision ---
+
+#[derive(Debug, Clone)]
+pub(crate) enum Decision {
+    Allow,
+    Block,
+    Redirect(Url),
+}
+
+enum GuardState {
+    Pending,
+    Decided(Decision),
+    // The resume callback is set by the routing service once the guards have been asked.
+    Deferred(Option<Box<dyn FnOnce(Decision)>>),
+    Resolved,
+}
+
+// --- Navigation ---
+
+/// A navigation waiting for the decision of navigation guards.
+///
+/// - The navigation is allowed when no guard decides otherwise.
+/// - `block` and `redirect` override `allow` of other guards.
+/// - `defer` postpones the decision - keep the `Navigation` (e.g. in your `Model` while a modal is open)
+///   and call `allow`, `block` or `redirect` later.
+#[derive(Clone)]
+pub struct Navigation {
+    url: Url,
+    kind: NavigationKind,
+    state: Rc<RefCell<GuardState>>,
+}
+
+impl Navigation {
+    pub(crate) fn new(url: Url, kind: NavigationKind) -> Self {
+        Self {
+            url,
+            kind,
+            state: Rc::new(RefCell::new(GuardState::Pending)),
+        }
+    }
+
+    /// The requested url.
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub const fn kind(&self) -> NavigationKind {
+        self.kind
+    }
+
+    /// Returns `true` while the navigation is deferred and waits for `allow`, `block` or `redirect`.
+    pub fn is_deferred(&self) -> bool {
+        matches!(*self.state.borrow(), GuardState::Deferred(_))
+    }
+
+    /// Let the navigation continue.
+    pub fn allow(self) {
+        self.decide(Decision::Allow);
+    }
+
+    /// Cancel the navigation.
+    pub fn block(self) {
+        self.decide(Decision::Block);
+    }
+
+    /// Cancel the navigation and navigate to the `url` instead.
+    pub fn redirect(self, url: Url) {
+        self.decide(Decision::Redirect(url));
+    }
+
+    /// Postpone the decision - e.g. until the user confirms a modal.
+    /// The url isn't changed until the navigation is allowed.
+    pub fn defer(&self) {
+        let mut state = self.state.borrow_mut();
+        if matches!(
+            *state,
+            GuardState::Pending | GuardState::Decided(Decision::Allow)
+        ) {
+            *state = GuardState::Deferred(None);
+        }
+    }
+
+    fn decide(&self, decision: Decision) {
+        let mut state = self.state.borrow_mut();
+        match &mut *state {
+            GuardState::Pending | GuardState::Decided(Decision::Allow) => {
+                *state = GuardState::Decided(decision);
+            }
+            GuardState::Deferred(resume) => match resume.take() {
+                Some(resume) => {
+                    *state = GuardState::Resolved;
+                    drop(state);
+                    resume(decision);
+                }
+                None => *state = GuardState::Decided(decision),
+            },
+            GuardState::Decided(_) | GuardState::Resolved => (),
+        }
+    }
+
+    /// Get the decision of guards once they've been notified.
+    /// Returns `None` when the navigation has been deferred.
+    pub(crate) fn take_decision(&self) -> Option<Decision> {
+        let mut state = self.state.borrow_mut();
+        match mem::replace(&mut *state, GuardState::Resolved) {
+            GuardState::Pending => Some(Decision::Allow),
+            GuardState::Decided(decision) => Some(decision),
+            deferred @ GuardState::Deferred(_) => {
+                *state = deferred;
+                None
+            }
+            GuardState::Resolved => None,
+        }
+    }
+
+    /// Call `resume` when the deferred navigation is resolved.
+    pub(crate) fn on_resolved(&self, resume: impl FnOnce(Decision) + 'static) {
+        if let GuardState::Deferred(stored_resume) = &mut *self.state.borrow_mut() {
+            *stored_resume = Some(Box::new(resume));
+        }
+    }
+}
+
+impl std::fmt::Debug for Navigation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Navigation")
+            .field("url", &self.url)
+            .field("kind", &self.kind)
+            .field("deferred", &self.is_deferred())
+            .finish()
+    }
+}
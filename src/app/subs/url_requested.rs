@@ -11,6 +11,10 @@ pub type PreventDefault = bool;
 
 /// Subscribe to url requests. Requests are fired on `<a>` link click.
 ///
+/// Links to other origins, outside of the base path or with `rel="external"` are requested too -
+/// see `UrlRequest::kind`. Seed doesn't intercept them (unless the request is
+/// `handled_and_prevent_refresh`).
+///
 /// _Note:_ `orders.notify(subs::UrlRequested::new(url))` simulates link click.
 ///
 /// # Example
@@ -44,19 +48,37 @@ impl Default for UrlRequestStatus {
     }
 }
 
+// --- UrlRequestKind ---
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum UrlRequestKind {
+    /// A same-origin link inside the base path - Seed routes it.
+    #[default]
+    Internal,
+    /// A link to another origin, outside of the base path or with `rel="external"`
+    /// - the browser follows it. Contains the resolved absolute href.
+    External(String),
+}
+
 // --- UrlRequest ---
 
 #[derive(Debug, Clone)]
 pub struct UrlRequest {
     pub(crate) status: Rc<Cell<UrlRequestStatus>>,
     pub(crate) event: Rc<RefCell<Option<Event>>>,
+    pub(crate) kind: UrlRequestKind,
 }
 
 impl UrlRequest {
-    pub(crate) fn new(status: UrlRequestStatus, event: Option<Event>) -> Self {
+    pub(crate) fn new(
+        status: UrlRequestStatus,
+        event: Option<Event>,
+        kind: UrlRequestKind,
+    ) -> Self {
         Self {
             status: Rc::new(Cell::new(status)),
             event: Rc::new(RefCell::new(event)),
+            kind,
         }
     }
 }
@@ -66,6 +88,7 @@ impl Default for UrlRequest {
         Self {
             status: Rc::new(Cell::new(UrlRequestStatus::default())),
             event: Rc::new(RefCell::new(None)),
+            kind: UrlRequestKind::default(),
         }
     }
 }
@@ -73,6 +96,7 @@ impl Default for UrlRequest {
 impl UrlRequest {
     /// Flag the url request as unhandled.
     /// - Seed prevents page refresh, pushes the route and fires `UrlChanged` notification.
+    /// - External links are followed by the browser.
     /// - It's the default behaviour.
     pub fn unhandled(self) {
         self.status.set(UrlRequestStatus::Unhandled);
@@ -93,4 +117,12 @@ impl UrlRequest {
     pub fn status(&self) -> UrlRequestStatus {
         self.status.get()
     }
+
+    pub const fn kind(&self) -> &UrlRequestKind {
+        &self.kind
+    }
+
+    pub const fn is_external(&self) -> bool {
+        matches!(self.kind, UrlRequestKind::External(_))
+    }
 }
@@ -0,0 +1,332 @@
+//! Time-travel debugging - record processed messages together with model snapshots,
+//! jump to any past state and replay the message log.
+//!
+//! Enable the crate feature `time-travel` and call `App::enable_time_travel`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let app = App::start("app", init, update, view);
+//! let time_travel = app.enable_time_travel();
+//! // Control it from the browser console through `window.__seed_debug`.
+//! time_travel.expose_to_js();
+//! ```
+//!
+//! In the browser console:
+//!
+//! ```js
+//! __seed_debug.messages()  // ["Increment", "Decrement", ..]
+//! __seed_debug.jumpTo(1)   // the state after the first message
+//! __seed_debug.jumpTo(__seed_debug.length()) // back to the present
+//! __seed_debug.replay()    // replay all messages from the state after `init`
+//! ```
+
+use super::App;
+use crate::browser::util;
+use crate::virtual_dom::IntoNodes;
+use std::{cell::RefCell, fmt, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsValue};
+
+const JS_HANDLE_NAME: &str = "__seed_debug";
+
+// ------ TimeTravel ------
+
+/// Handle to the time-travel debugger created by `App::enable_time_travel`.
+pub struct TimeTravel<Ms, Mdl, INodes>
+where
+    Ms: 'static,
+    Mdl: 'static,
+    INodes: IntoNodes<Ms>,
+{
+    app: App<Ms, Mdl, INodes>,
+    history: Rc<RefCell<History<Ms, Mdl>>>,
+}
+
+impl<Ms, Mdl, INodes> Clone for TimeTravel<Ms, Mdl, INodes>
+where
+    INodes: IntoNodes<Ms>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            app: self.app.clone(),
+            history: Rc::clone(&self.history),
+        }
+    }
+}
+
+impl<Ms, Mdl, INodes> fmt::Debug for TimeTravel<Ms, Mdl, INodes>
+where
+    INodes: IntoNodes<Ms>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TimeTravel")
+    }
+}
+
+impl<Ms, Mdl, INodes> App<Ms, Mdl, INodes>
+where
+    Ms: Clone + fmt::Debug + 'static,
+    Mdl: Clone + 'static,
+    INodes: IntoNodes<Ms> + 'static,
+{
+    /// Start recording processed messages together with model snapshots.
+    ///
+    /// The history is kept until the `App` is unmounted.
+    pub fn enable_time_travel(&self) -> TimeTravel<Ms, Mdl, INodes> {
+        let history = Rc::new(RefCell::new(History::default()));
+
+        let recorder: Rc<dyn Recorder<Ms, Mdl>> = Rc::clone(&history) as _;
+        if let Some(old_recorder) = self.data.update_recorder.replace(Some(recorder)) {
+            old_recorder.stop();
+        }
+
+        TimeTravel {
+            app: self.clone(),
+            history,
+        }
+    }
+}
+
+impl<Ms, Mdl, INodes> TimeTravel<Ms, Mdl, INodes>
+where
+    Ms: Clone + fmt::Debug + 'static,
+    Mdl: Clone + 'static,
+    INodes: IntoNodes<Ms> + 'static,
+{
+    /// The number of recorded messages.
+    pub fn len(&self) -> usize {
+        self.history.borrow().entries.len()
+    }
+
+    /// Returns `true` if no messages have been recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Recorded messages in the order they were processed.
+    pub fn messages(&self) -> Vec<Ms> {
+        self.history
+            .borrow()
+            .entries
+            .iter()
+            .map(|entry| entry.msg.clone())
+            .collect()
+    }
+
+    /// The number of messages applied to the currently displayed state.
+    /// It's equal to `len()` when the app isn't in the past.
+    pub fn position(&self) -> usize {
+        let history = self.history.borrow();
+        history.position.unwrap_or(history.entries.len())
+    }
+
+    /// Restore the state after the first `position` messages and rerender.
+    /// `jump_to(len())` returns to the present.
+    ///
+    /// A new message processed while the app is in the past discards the later history.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `position` is greater than `len()`.
+    pub fn jump_to(&self, position: usize) {
+        let model = {
+            let mut history = self.history.borrow_mut();
+            let len = history.entries.len();
+            assert!(
+                position <= len,
+                "position {position} is out of range 0..={len}"
+            );
+
+            if history.position.is_none() {
+                history.present = self.app.data.model.borrow().clone();
+            }
+            if position == len {
+                history.position = None;
+                history.present.take()
+            } else {
+                history.position = Some(position);
+                Some(history.entries[position].model.clone())
+            }
+        };
+        if model.is_some() {
+            self.app.data.model.replace(model);
+            self.app.update_with_option(None);
+        }
+    }
+
+    /// Restore the state after `init` and process all recorded messages again.
+    ///
+    /// Messages are passed directly to `update` - middlewares are skipped because they've been applied
+    /// before the messages were recorded. The history isn't changed by the replay.
+    ///
+    /// _Note:_ `update` is invoked again for each message, so also cmds and other effects
+    /// are performed again.
+    pub fn replay(&self) {
+        let (initial_model, messages) = {
+            let mut history = self.history.borrow_mut();
+            let Some(initial_model) = history.entries.first().map(|entry| entry.model.clone())
+            else {
+                return;
+            };
+            history.position = None;
+            history.present = None;
+            let messages = history
+                .entries
+                .iter()
+                .map(|entry| entry.msg.clone())
+                .collect::<Vec<_>>();
+            (initial_model, messages)
+        };
+        self.app.data.model.replace(Some(initial_model));
+
+        // Recorded messages have already passed through middlewares
+        // and they are already in the history.
+        let middleware_count = self.app.data.middlewares.borrow().len();
+        let recorder = self.app.data.update_recorder.take();
+        for msg in messages {
+            self.app.process_effect_queue(
+                self.app
+                    .process_queue_message_from_middleware(Some(msg), middleware_count),
+            );
+        }
+        if self.app.data.unmounted.get() {
+            if let Some(recorder) = recorder {
+                recorder.stop();
+            }
+            return;
+        }
+        self.app.data.update_recorder.replace(recorder);
+        self.app.update_with_option(None);
+    }
+
+    /// Expose the debugger as `window.__seed_debug` with methods
+    /// `length()`, `position()`, `messages()`, `jumpTo(position)` and `replay()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the JS object can't be created.
+    pub fn expose_to_js(&self) {
+        let handle = js_sys::Object::new();
+        let mut closures = Vec::new();
+
+        let mut add_method = |name: &str, closure: Closure<dyn Fn(JsValue) -> JsValue>| {
+            js_sys::Reflect::set(&handle, &name.into(), closure.as_ref())
+                .expect("set `__seed_debug` method");
+            closures.push(closure);
+        };
+
+        let time_travel = self.clone();
+        add_method(
+            "length",
+            Closure::new(move |_: JsValue| JsValue::from(time_travel.len())),
+        );
+        let time_travel = self.clone();
+        add_method(
+            "position",
+            Closure::new(move |_: JsValue| JsValue::from(time_travel.position())),
+        );
+        let time_travel = self.clone();
+        add_method(
+            "messages",
+            Closure::new(move |_: JsValue| {
+                time_travel
+                    .messages()
+                    .iter()
+                    .map(|msg| JsValue::from(format!("{msg:?}")))
+                    .collect::<js_sys::Array>()
+                    .into()
+            }),
+        );
+        let time_travel = self.clone();
+        add_method(
+            "jumpTo",
+            Closure::new(move |position: JsValue| {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let position = position.as_f64().expect("`position` has to be a number") as usize;
+                time_travel.jump_to(position);
+                JsValue::UNDEFINED
+            }),
+        );
+        let time_travel = self.clone();
+        add_method(
+            "replay",
+            Closure::new(move |_: JsValue| {
+                time_travel.replay();
+                JsValue::UNDEFINED
+            }),
+        );
+
+        js_sys::Reflect::set(&util::window(), &JS_HANDLE_NAME.into(), &handle)
+            .expect("set `window.__seed_debug`");
+        let old_closures = std::mem::replace(&mut self.history.borrow_mut().js_closures, closures);
+        drop(old_closures);
+    }
+}
+
+// ------ Recorder ------
+
+/// Records messages before they are passed to `update`. See `AppData::update_recorder`.
+pub(crate) trait Recorder<Ms, Mdl> {
+    fn record(&self, msg: &Ms, model: &Mdl);
+
+    /// Stop recording and release resources - e.g. when the `App` is unmounted.
+    fn stop(&self);
+}
+
+impl<Ms: Clone, Mdl: Clone> Recorder<Ms, Mdl> for RefCell<History<Ms, Mdl>> {
+    fn record(&self, msg: &Ms, model: &Mdl) {
+        self.borrow_mut().record(msg.clone(), model.clone());
+    }
+
+    fn stop(&self) {
+        // Closures reference the history - take them out to drop them after the borrow ends.
+        let js_closures = self.borrow_mut().take_js_handle();
+        drop(js_closures);
+    }
+}
+
+// ------ History ------
+
+struct Entry<Ms, Mdl> {
+    msg: Ms,
+    // The model before `msg` was processed.
+    model: Mdl,
+}
+
+struct History<Ms, Mdl> {
+    entries: Vec<Entry<Ms, Mdl>>,
+    // `None` means the app is in the present.
+    position: Option<usize>,
+    // The present model saved while the app is in the past.
+    present: Option<Mdl>,
+    js_closures: Vec<Closure<dyn Fn(JsValue) -> JsValue>>,
+}
+
+impl<Ms, Mdl> Default for History<Ms, Mdl> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            position: None,
+            present: None,
+            js_closures: Vec::new(),
+        }
+    }
+}
+
+impl<Ms, Mdl> History<Ms, Mdl> {
+    fn record(&mut self, msg: Ms, model: Mdl) {
+        if let Some(position) = self.position.take() {
+            self.entries.truncate(position);
+            self.present = None;
+        }
+        self.entries.push(Entry { msg, model });
+    }
+
+    /// Remove `window.__seed_debug` and return its closures.
+    fn take_js_handle(&mut self) -> Vec<Closure<dyn Fn(JsValue) -> JsValue>> {
+        if !self.js_closures.is_empty() {
+            let _ = js_sys::Reflect::delete_property(&util::window(), &JS_HANDLE_NAME.into());
+        }
+        std::mem::take(&mut self.js_closures)
+    }
+}
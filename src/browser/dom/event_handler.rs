@@ -2,23 +2,16 @@
 //! `web_sys::Event`
 
 use super::super::util;
+use crate::app::IntoOptionMsg;
 use crate::virtual_dom::{Ev, EventHandler};
-use std::rc::Rc;
 use wasm_bindgen::JsCast;
 
 /// Create an event that passes a String of field text, for fast input handling.
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn input_ev<Ms: 'static, MsU: 'static>(
+pub fn input_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(String) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(String) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
         let value = event
             .target()
@@ -27,122 +20,81 @@ pub fn input_ev<Ms: 'static, MsU: 'static>(
             .and_then(util::get_value)
             .map_err(crate::error)
             .unwrap_or_default();
-        handler(value)
+        handler.clone()(value).into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
 
 /// Create an event that passes a `web_sys::KeyboardEvent`, allowing easy access
 /// to items like `key_code`() and key().
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn keyboard_ev<Ms: 'static, MsU: 'static>(
+pub fn keyboard_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::KeyboardEvent) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::KeyboardEvent) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
-        handler(event.dyn_ref::<web_sys::KeyboardEvent>().unwrap().clone())
+        handler.clone()(event.dyn_ref::<web_sys::KeyboardEvent>().unwrap().clone())
+            .into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
 
 /// See `keyboard_ev`
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn mouse_ev<Ms: 'static, MsU: 'static>(
+pub fn mouse_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::MouseEvent) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::MouseEvent) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
-        handler(event.dyn_ref::<web_sys::MouseEvent>().unwrap().clone())
+        handler.clone()(event.dyn_ref::<web_sys::MouseEvent>().unwrap().clone()).into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
 
 /// See `keyboard_ev`
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn touch_ev<Ms: 'static, MsU: 'static>(
+pub fn touch_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::TouchEvent) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::TouchEvent) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
-        handler(event.dyn_ref::<web_sys::TouchEvent>().unwrap().clone())
+        handler.clone()(event.dyn_ref::<web_sys::TouchEvent>().unwrap().clone()).into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
 
 /// See `keyboard_ev`
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn drag_ev<Ms: 'static, MsU: 'static>(
+pub fn drag_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::DragEvent) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::DragEvent) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
-        handler(event.dyn_ref::<web_sys::DragEvent>().unwrap().clone())
+        handler.clone()(event.dyn_ref::<web_sys::DragEvent>().unwrap().clone()).into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
 
 /// See `keyboard_ev`
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn pointer_ev<Ms: 'static, MsU: 'static>(
+pub fn pointer_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::PointerEvent) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::PointerEvent) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
-        handler(event.dyn_ref::<web_sys::PointerEvent>().unwrap().clone())
+        handler.clone()(event.dyn_ref::<web_sys::PointerEvent>().unwrap().clone()).into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
 
 /// See `keyboard_ev`
-#[allow(clippy::shadow_unrelated)]
 #[allow(clippy::missing_panics_doc)]
-pub fn wheel_ev<Ms: 'static, MsU: 'static>(
+pub fn wheel_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::WheelEvent) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::WheelEvent) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
     let handler = move |event: web_sys::Event| {
-        handler(event.dyn_ref::<web_sys::WheelEvent>().unwrap().clone())
+        handler.clone()(event.dyn_ref::<web_sys::WheelEvent>().unwrap().clone()).into_option_msg()
     };
     EventHandler::new(trigger, handler)
 }
@@ -150,7 +102,7 @@ pub fn wheel_ev<Ms: 'static, MsU: 'static>(
 /// Create an event that accepts a closure, and passes a `web_sys::Event`, allowing full control of
 /// event-handling.
 #[deprecated(since = "0.6.0", note = "Use `ev` instead.")]
-pub fn raw_ev<Ms: 'static, MsU: 'static>(
+pub fn raw_ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::Event) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
@@ -160,25 +112,14 @@ pub fn raw_ev<Ms: 'static, MsU: 'static>(
 /// Create an event handler that accepts a closure, and passes a `web_sys::Event`, allowing full control of
 /// event-handling.
 ///
-/// Handler has to return `Msg`, `Option<Msg>` or `()`.
-///
-/// # Panics
-///
-/// Panics when the handler doesn't return `Msg` or `()`. (It will be changed to a compile-time error).
-#[allow(clippy::shadow_unrelated)]
-#[allow(clippy::missing_panics_doc)]
-pub fn ev<Ms: 'static, MsU: 'static>(
+/// Handler has to return `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
+pub fn ev<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     trigger: impl Into<Ev>,
     handler: impl FnOnce(web_sys::Event) -> MsU + 'static + Clone,
 ) -> EventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::Event) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
-    #[allow(clippy::redundant_closure)]
-    EventHandler::new(trigger, move |event| handler(event))
+    EventHandler::new(trigger, move |event| {
+        handler.clone()(event).into_option_msg()
+    })
 }
 
 /// Create an event that passes no data, other than it occurred. Foregoes using a closure,
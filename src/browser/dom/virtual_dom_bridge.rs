@@ -304,6 +304,63 @@ pub(crate) fn patch_el_details<Ms>(
     }
 }
 
+/// Update attributes and style of an existing (e.g. prerendered) DOM element
+/// so they match the given `El`.
+///
+/// Returns names of the attributes that had to be changed.
+pub(crate) fn hydrate_el_details<Ms>(el: &mut El<Ms>, el_ws: &web_sys::Node) -> Vec<String> {
+    let mut changed_attrs = Vec::new();
+    let Ok(element) = node_to_element(el_ws) else {
+        return changed_attrs;
+    };
+    let has_style = el.style.vals.keys().len() > 0;
+
+    fix_attrs_order(&mut el.attrs);
+    for (at, at_value) in &el.attrs.vals {
+        match at {
+            // `Style` has higher priority than `At::Style` - see `make_websys_el`.
+            At::Style if has_style => continue,
+            // The namespace is given by the parser, the attribute itself isn't important.
+            At::Xmlns if el.namespace.is_some() => continue,
+            _ => (),
+        }
+        let current_value = element.get_attribute(at.as_str());
+        let up_to_date = match at_value {
+            AtValue::Some(value) => current_value.as_deref() == Some(value.as_str()),
+            AtValue::None => current_value.as_deref() == Some(""),
+            AtValue::Ignored => current_value.is_none(),
+        };
+        if !up_to_date {
+            set_attr_value(el_ws, at, at_value);
+            changed_attrs.push(at.as_str().to_owned());
+        }
+    }
+
+    if has_style && element.get_attribute("style") != Some(el.style.to_string()) {
+        set_style(el_ws, &el.style);
+        changed_attrs.push("style".to_owned());
+    }
+
+    for name in element
+        .get_attribute_names()
+        .iter()
+        .filter_map(|name| name.as_string())
+    {
+        let expected = match name.as_str() {
+            "style" if has_style => true,
+            "xmlns" if el.namespace.is_some() => true,
+            _ => el.attrs.vals.contains_key(&At::from(name.clone())),
+        };
+        if !expected {
+            element
+                .remove_attribute(&name)
+                .expect("Problem removing an attribute");
+            changed_attrs.push(name);
+        }
+    }
+    changed_attrs
+}
+
 /// Some elements have order-sensitive attributes.
 ///
 /// See the [example](https://github.com/seed-rs/seed/issues/335) of such element.
@@ -535,7 +592,7 @@ pub(crate) fn replace_child(new: &web_sys::Node, old: &web_sys::Node, parent: &w
 }
 
 #[inline]
-fn wire_up_el<Ms>(el: &mut El<Ms>, mailbox: &Mailbox<Ms>) {
+pub(crate) fn wire_up_el<Ms>(el: &mut El<Ms>, mailbox: &Mailbox<Ms>) {
     let node_ws = el
         .node_ws
         .as_ref()
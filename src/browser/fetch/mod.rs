@@ -0,0 +1,251 @@
+//! HTTP requests through the browser [Fetch API](https://developer.mozilla.org/en-US/docs/Web/API/Fetch_API).
+//!
+//! Enable the crate feature `fetch`.
+//!
+//! Requests are futures, so they can be passed to `orders.perform_cmd` directly.
+//! The request is aborted (through `AbortController`) when its future is dropped -
+//! e.g. when you drop the `CmdHandle` returned from `orders.perform_cmd_with_handle`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! async fn fetch_user(id: u32) -> fetch::Result<User> {
+//!     Request::new(format!("/api/users/{id}"))
+//!         .query(UrlSearch::new(vec![("fields", vec!["name", "email"])]))
+//!         .timeout(5000)
+//!         .fetch()
+//!         .await?
+//!         .check_status()?
+//!         .json()
+//!         .await
+//! }
+//!
+//! fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+//!     match msg {
+//!         Msg::FetchUser(id) => {
+//!             model.request = Some(orders.perform_cmd_with_handle(async move {
+//!                 Msg::UserFetched(fetch_user(id).await)
+//!             }));
+//!         }
+//!         // Dropping the handle aborts the request.
+//!         Msg::Cancel => model.request = None,
+//!         Msg::UserFetched(Ok(user)) => model.user = Some(user),
+//!         Msg::UserFetched(Err(fetch::FetchError::Status(status))) => ...,
+//!         Msg::UserFetched(Err(error)) => ...,
+//!     }
+//! }
+//! ```
+
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+mod request;
+mod response;
+
+pub use request::{Method, Request};
+pub use response::{Response, Status};
+
+pub type Result<T> = std::result::Result<T, FetchError>;
+
+/// Send a `GET` request to the `url`.
+///
+/// A shortcut for `Request::new(url).fetch()`.
+pub async fn fetch(url: impl Into<String>) -> Result<Response> {
+    Request::new(url).fetch().await
+}
+
+// ------ FetchError ------
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// The request couldn't be created - e.g. an invalid header name or an unserializable body.
+    Request(JsValue),
+    /// The request failed or the body couldn't be read - e.g. the server is unreachable,
+    /// the request has been blocked by CORS or aborted.
+    Network(JsValue),
+    /// There was no response in the time set by `Request::timeout`.
+    Timeout,
+    /// The response status isn't successful (2xx) - see `Response::check_status`.
+    Status(Status),
+    /// The body couldn't be decoded - e.g. it isn't valid JSON or the JSON doesn't match the target type.
+    Decode(JsValue),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Request(error) => write!(f, "invalid request: {error:?}"),
+            Self::Network(error) => write!(f, "network error: {error:?}"),
+            Self::Timeout => write!(f, "request timed out"),
+            Self::Status(status) => write!(f, "response status {} {}", status.code, status.text),
+            Self::Decode(error) => write!(f, "response body couldn't be decoded: {error:?}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+// ------ AbortGuard ------
+
+/// Aborts the request when dropped before it's disarmed.
+#[derive(Debug)]
+struct AbortGuard(Option<web_sys::AbortController>);
+
+impl AbortGuard {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for AbortGuard {
+    fn drop(&mut self) {
+        if let Some(controller) = self.0.take() {
+            controller.abort();
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::cmd_manager::CmdManager;
+    use crate::browser::{util::window, UrlSearch};
+    use gloo_timers::future::TimeoutFuture;
+    use serde::Deserialize;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    /// Replace `window.fetch` with a function that stores the request
+    /// to `window.__fetch_request` and returns the result of `fetch_body`.
+    /// Returns the original `fetch`.
+    fn mock_fetch(fetch_body: &str) -> JsValue {
+        let original = js_sys::Reflect::get(&window(), &"fetch".into()).unwrap();
+        let mock = js_sys::Function::new_with_args(
+            "request",
+            &format!("window.__fetch_request = request; {fetch_body}"),
+        );
+        js_sys::Reflect::set(&window(), &"fetch".into(), &mock).unwrap();
+        original
+    }
+
+    fn respond_with(body: &str, status: u16) -> JsValue {
+        let body = String::from(js_sys::JSON::stringify(&body.into()).unwrap());
+        mock_fetch(&format!(
+            "return Promise.resolve(new Response({body}, {{ status: {status} }}));"
+        ))
+    }
+
+    fn restore_fetch(original: &JsValue) {
+        js_sys::Reflect::set(&window(), &"fetch".into(), original).unwrap();
+    }
+
+    fn window_property(name: &str) -> JsValue {
+        js_sys::Reflect::get(&window(), &name.into()).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn fetch_json() {
+        let original = respond_with(r#"{ "name": "Martin" }"#, 200);
+
+        let user = Request::new("/users/1")
+            .method(Method::Post)
+            .query(UrlSearch::new(vec![("fields", vec!["name"])]))
+            .header("X-Test", "yes")
+            .text("hello")
+            .fetch()
+            .await
+            .unwrap()
+            .check_status()
+            .unwrap()
+            .json::<User>()
+            .await;
+        restore_fetch(&original);
+
+        assert_eq!(
+            user.unwrap(),
+            User {
+                name: "Martin".to_owned()
+            }
+        );
+        let request = window_property("__fetch_request").unchecked_into::<web_sys::Request>();
+        assert_eq!(request.method(), "POST");
+        assert!(request.url().ends_with("/users/1?fields=name"));
+        assert_eq!(
+            request.headers().get("X-Test").unwrap().as_deref(),
+            Some("yes")
+        );
+        assert_eq!(
+            request.headers().get("Content-Type").unwrap().as_deref(),
+            Some("text/plain; charset=utf-8")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    async fn status_and_decode_errors() {
+        let original = respond_with("Not Found", 404);
+        let result = fetch("/users/2").await.unwrap().check_status();
+        restore_fetch(&original);
+        match result {
+            Err(FetchError::Status(status)) => assert_eq!(status.code, 404),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        let original = respond_with("not JSON", 200);
+        let result = fetch("/users/3").await.unwrap().json::<User>().await;
+        restore_fetch(&original);
+        assert!(matches!(result, Err(FetchError::Decode(_))));
+
+        let original = respond_with(r#"{ "id": 3 }"#, 200);
+        let result = fetch("/users/3").await.unwrap().json::<User>().await;
+        restore_fetch(&original);
+        assert!(matches!(result, Err(FetchError::Decode(_))));
+
+        let original = mock_fetch("return Promise.reject(new TypeError('Failed to fetch'));");
+        let result = fetch("/users/4").await;
+        restore_fetch(&original);
+        assert!(matches!(result, Err(FetchError::Network(_))));
+    }
+
+    const NEVER_RESPOND: &str = "
+        window.__fetch_aborted = false;
+        return new Promise((resolve, reject) => request.signal.addEventListener('abort', () => {
+            window.__fetch_aborted = true;
+            reject(new DOMException('Aborted', 'AbortError'));
+        }));
+    ";
+
+    #[wasm_bindgen_test]
+    async fn timeout_aborts_request() {
+        let original = mock_fetch(NEVER_RESPOND);
+        let result = Request::new("/slow").timeout(10).fetch().await;
+        restore_fetch(&original);
+
+        assert!(matches!(result, Err(FetchError::Timeout)));
+        assert_eq!(window_property("__fetch_aborted"), JsValue::TRUE);
+    }
+
+    #[wasm_bindgen_test]
+    async fn dropped_cmd_handle_aborts_request() {
+        let original = mock_fetch(NEVER_RESPOND);
+        let handle = CmdManager::perform_cmd_with_handle(async {
+            let _ = fetch("/slow").await;
+        });
+        TimeoutFuture::new(10).await;
+        assert_eq!(window_property("__fetch_aborted"), JsValue::FALSE);
+
+        drop(handle);
+        TimeoutFuture::new(10).await;
+        restore_fetch(&original);
+
+        assert_eq!(window_property("__fetch_aborted"), JsValue::TRUE);
+    }
+}
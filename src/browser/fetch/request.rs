@@ -0,0 +1,214 @@
+use super::{AbortGuard, FetchError, Response, Result};
+use crate::browser::{json, util::window, UrlSearch};
+use futures::future::{self, Either};
+use gloo_timers::future::TimeoutFuture;
+use serde::Serialize;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::RequestCredentials;
+
+// ------ Method ------
+
+/// HTTP request method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Method {
+    #[default]
+    Get,
+    Head,
+    Post,
+    Put,
+    Delete,
+    Connect,
+    Options,
+    Trace,
+    Patch,
+}
+
+impl Method {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Head => "HEAD",
+            Self::Post => "POST",
+            Self::Put => "PUT",
+            Self::Delete => "DELETE",
+            Self::Connect => "CONNECT",
+            Self::Options => "OPTIONS",
+            Self::Trace => "TRACE",
+            Self::Patch => "PATCH",
+        }
+    }
+}
+
+// ------ Request ------
+
+/// HTTP request builder.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let response = Request::new("/api/users")
+///     .method(Method::Post)
+///     .header("Authorization", format!("Bearer {token}"))
+///     .json(&new_user)?
+///     .fetch()
+///     .await?;
+/// ```
+#[derive(Debug)]
+pub struct Request {
+    url: String,
+    method: Method,
+    headers: Vec<(String, String)>,
+    body: Option<JsValue>,
+    credentials: Option<RequestCredentials>,
+    timeout: Option<u32>,
+}
+
+impl Request {
+    /// Create a `GET` request to the `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            method: Method::default(),
+            headers: Vec::new(),
+            body: None,
+            credentials: None,
+            timeout: None,
+        }
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Set the header `name` to the `value`. It replaces the previously set value.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers
+            .retain(|(old_name, _)| !old_name.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Append the `search` to the url's query string.
+    pub fn query(mut self, search: impl Into<UrlSearch>) -> Self {
+        let search = search.into().to_string();
+        if !search.is_empty() {
+            let separator = if self.url.contains('?') { '&' } else { '?' };
+            self.url.push(separator);
+            self.url.push_str(&search);
+        }
+        self
+    }
+
+    /// Set the body to the `data` serialized to JSON
+    /// and the header `Content-Type` to `application/json; charset=utf-8` (if not set yet).
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Request` when the `data` can't be serialized.
+    pub fn json<T: Serialize + ?Sized>(self, data: &T) -> Result<Self> {
+        let body = json::to_js_value(data)
+            .and_then(|value| js_sys::JSON::stringify(&value))
+            .map_err(FetchError::Request)?;
+        Ok(self.body(body.into(), "application/json; charset=utf-8"))
+    }
+
+    /// Set the body to the `text`
+    /// and the header `Content-Type` to `text/plain; charset=utf-8` (if not set yet).
+    pub fn text(self, text: impl AsRef<str>) -> Self {
+        self.body(text.as_ref().into(), "text/plain; charset=utf-8")
+    }
+
+    /// Set the body to the `bytes`
+    /// and the header `Content-Type` to `application/octet-stream` (if not set yet).
+    pub fn bytes(self, bytes: &[u8]) -> Self {
+        self.body(
+            js_sys::Uint8Array::from(bytes).into(),
+            "application/octet-stream",
+        )
+    }
+
+    /// Set the body to the `form_data`.
+    ///
+    /// The header `Content-Type` (`multipart/form-data` with a boundary) is set by the browser.
+    pub fn form_data(mut self, form_data: web_sys::FormData) -> Self {
+        self.body = Some(form_data.into());
+        self
+    }
+
+    /// Set whether the browser sends cookies and other credentials.
+    /// The browser's default is `RequestCredentials::SameOrigin`.
+    pub fn credentials(mut self, credentials: RequestCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Abort the request and fail with `FetchError::Timeout` when the response doesn't arrive
+    /// in `timeout` milliseconds.
+    ///
+    /// _Note:_ Reading the body isn't limited by the timeout.
+    pub fn timeout(mut self, timeout: u32) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Send the request.
+    ///
+    /// The request is aborted when the returned future or the `Response` (before its body is read)
+    /// is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Request`, `FetchError::Network` or `FetchError::Timeout`.
+    /// Unsuccessful statuses aren't errors - see `Response::check_status`.
+    pub async fn fetch(self) -> Result<Response> {
+        let controller = web_sys::AbortController::new().map_err(FetchError::Request)?;
+        let request = self.create_request(&controller.signal())?;
+        let abort_guard = AbortGuard(Some(controller));
+
+        let response = JsFuture::from(window().fetch_with_request(&request));
+        let response = match self.timeout {
+            Some(timeout) => match future::select(response, TimeoutFuture::new(timeout)).await {
+                Either::Left((response, _)) => response,
+                // The request is aborted by `abort_guard`.
+                Either::Right(_) => return Err(FetchError::Timeout),
+            },
+            None => response.await,
+        }
+        .map_err(FetchError::Network)?;
+
+        Ok(Response::new(response.unchecked_into(), abort_guard))
+    }
+
+    fn body(mut self, body: JsValue, content_type: &str) -> Self {
+        self.body = Some(body);
+        let has_content_type = self
+            .headers
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case("Content-Type"));
+        if has_content_type {
+            self
+        } else {
+            self.header("Content-Type", content_type)
+        }
+    }
+
+    fn create_request(&self, signal: &web_sys::AbortSignal) -> Result<web_sys::Request> {
+        let headers = web_sys::Headers::new().map_err(FetchError::Request)?;
+        for (name, value) in &self.headers {
+            headers.set(name, value).map_err(FetchError::Request)?;
+        }
+
+        let mut init = web_sys::RequestInit::new();
+        init.method(self.method.as_str())
+            .headers(&headers)
+            .body(self.body.as_ref())
+            .signal(Some(signal));
+        if let Some(credentials) = self.credentials {
+            init.credentials(credentials);
+        }
+        web_sys::Request::new_with_str_and_init(&self.url, &init).map_err(FetchError::Request)
+    }
+}
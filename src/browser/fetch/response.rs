@@ -0,0 +1,115 @@
+use super::{AbortGuard, FetchError, Result};
+use crate::browser::json;
+use serde::de::DeserializeOwned;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+// ------ Status ------
+
+/// HTTP response status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// E.g. `404`.
+    pub code: u16,
+    /// E.g. `Not Found`.
+    pub text: String,
+}
+
+impl Status {
+    /// Returns `true` for 2xx codes.
+    pub const fn is_ok(&self) -> bool {
+        self.code >= 200 && self.code < 300
+    }
+}
+
+// ------ Response ------
+
+/// HTTP response.
+///
+/// The body is read by one of the consuming methods - `text`, `json` or `bytes`.
+#[derive(Debug)]
+pub struct Response {
+    raw: web_sys::Response,
+    abort_guard: AbortGuard,
+}
+
+impl Response {
+    pub(super) fn new(raw: web_sys::Response, abort_guard: AbortGuard) -> Self {
+        Self { raw, abort_guard }
+    }
+
+    pub fn status(&self) -> Status {
+        Status {
+            code: self.raw.status(),
+            text: self.raw.status_text(),
+        }
+    }
+
+    /// Returns the response if its status is successful (2xx).
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Status` otherwise.
+    pub fn check_status(self) -> Result<Self> {
+        let status = self.status();
+        if status.is_ok() {
+            Ok(self)
+        } else {
+            Err(FetchError::Status(status))
+        }
+    }
+
+    /// Get the value of the header `name`.
+    pub fn header(&self, name: &str) -> Option<String> {
+        self.raw.headers().get(name).ok().flatten()
+    }
+
+    /// The underlying `web_sys::Response`.
+    pub const fn raw_response(&self) -> &web_sys::Response {
+        &self.raw
+    }
+
+    /// Read the body as text.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Network` when the body can't be read
+    /// or `FetchError::Decode` when it isn't valid text.
+    pub async fn text(self) -> Result<String> {
+        let text = self.read_body(web_sys::Response::text).await?;
+        text.as_string().ok_or(FetchError::Decode(text))
+    }
+
+    /// Read the body as JSON and deserialize it to `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Network` when the body can't be read
+    /// or `FetchError::Decode` when it isn't valid JSON or doesn't match `T`.
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T> {
+        let text = self.text().await?;
+        let value = js_sys::JSON::parse(&text).map_err(FetchError::Decode)?;
+        json::from_js_value(&value).map_err(FetchError::Decode)
+    }
+
+    /// Read the body as bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FetchError::Network` when the body can't be read.
+    pub async fn bytes(self) -> Result<Vec<u8>> {
+        let buffer = self.read_body(web_sys::Response::array_buffer).await?;
+        Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+    }
+
+    async fn read_body(
+        mut self,
+        read: impl FnOnce(&web_sys::Response) -> std::result::Result<js_sys::Promise, JsValue>,
+    ) -> Result<JsValue> {
+        let promise = read(&self.raw).map_err(FetchError::Network)?;
+        let body = JsFuture::from(promise).await.map_err(FetchError::Network)?;
+        // The request is complete - there is nothing to abort.
+        self.abort_guard.disarm();
+        Ok(body)
+    }
+}
@@ -1,9 +1,16 @@
 pub mod dom;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod router;
 pub mod service;
+#[cfg(feature = "serde")]
+pub mod storage;
 pub mod url;
 pub mod util;
 
-#[cfg(feature = "routing")]
-mod json;
+#[cfg(feature = "serde")]
+pub(crate) mod json;
 
+#[cfg(feature = "serde")]
+pub use url::UrlSearchError;
 pub use url::{Url, UrlSearch, DUMMY_BASE_URL};
@@ -0,0 +1,547 @@
+//! Typed routes declared once - both parsing a `Url` into a route enum
+//! and generating a `Url` from a route are derived from the same path patterns.
+//!
+//! # Patterns
+//!
+//! - `/users` - literal path parts.
+//! - `/users/:id` - the path part is parsed into the field `id` by `FromStr` and written by `Display`.
+//! - `/search?query&page` - search (query) parameters. `Option<T>` fields are optional,
+//!   other fields are required.
+//! - `/admin/*page` - the remaining path parts are parsed by the field `page` - a nested `Routable`.
+//! - `/*` - the remaining path parts are ignored (e.g. a fallback route).
+//!
+//! Routes are tried in the declared order and the first matching one is used.
+//! Every field has to be bound in its pattern.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//!#[derive(Debug, Clone, PartialEq)]
+//!enum Route {
+//!    Home,
+//!    User { id: u32 },
+//!    Post { id: u32, post_id: u32, tab: Option<Tab> },
+//!    Admin(admin::Route),
+//!    NotFound,
+//!}
+//!
+//!routes! {
+//!    Route {
+//!        Home => "/",
+//!        User { id } => "/users/:id",
+//!        Post { id, post_id, tab } => "/users/:id/posts/:post_id?tab",
+//!        Admin(page) => "/admin/*page",
+//!        NotFound => "/*",
+//!    }
+//!}
+//!
+//!fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
+//!    orders.subscribe(|subs::UrlChanged(url)| Msg::RouteChanged(Route::from_url(&url)));
+//!    Model {
+//!        base_url: url.to_base_url(),
+//!        route: Route::from_url(&url),
+//!    }
+//!}
+//!
+//!fn view_post_link(model: &Model, id: u32, post_id: u32) -> Node<Msg> {
+//!    let route = Route::Post { id, post_id, tab: Some(Tab::Comments) };
+//!    a![attrs! {At::Href => route.append_to(model.base_url.clone())}, "Comments"]
+//!}
+//! ```
+
+use super::Url;
+use std::{fmt::Display, str::FromStr};
+
+// ------ Routable ------
+
+/// A route that can be parsed from a `Url` and written back to it.
+///
+/// Implement it by the macro `routes!`.
+pub trait Routable: Sized {
+    /// Parse the remaining path parts (see `Url::next_path_part`) and the search of the `url`.
+    /// Returns `None` when no route matches.
+    fn from_url(url: &Url) -> Option<Self>;
+
+    /// Append the route's path parts and search parameters to the `url` -
+    /// e.g. to the base url (see `Url::to_base_url`).
+    #[must_use]
+    fn append_to(&self, url: Url) -> Url;
+
+    /// Create a `Url` with only the route's path parts and search parameters.
+    fn to_url(&self) -> Url {
+        self.append_to(Url::new())
+    }
+}
+
+// ------ Pattern ------
+
+enum Segment<'a> {
+    Part(&'a str),
+    Param(&'a str),
+    // The name is empty when the remaining path parts are ignored.
+    Rest(&'a str),
+}
+
+struct Pattern<'a> {
+    segments: Vec<Segment<'a>>,
+    search_keys: Vec<&'a str>,
+}
+
+impl<'a> Pattern<'a> {
+    fn parse(pattern: &'a str) -> Self {
+        let (path, search) = pattern.split_once('?').unwrap_or((pattern, ""));
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                if let Some(name) = segment.strip_prefix(':') {
+                    Segment::Param(name)
+                } else if let Some(name) = segment.strip_prefix('*') {
+                    Segment::Rest(name)
+                } else {
+                    Segment::Part(segment)
+                }
+            })
+            .collect();
+        let search_keys = search.split('&').filter(|key| !key.is_empty()).collect();
+        Self {
+            segments,
+            search_keys,
+        }
+    }
+}
+
+// ------ Match ------
+
+/// The `Url` matched by a pattern - used by the macro `routes!`.
+#[doc(hidden)]
+pub struct Match<'a> {
+    params: Vec<(&'a str, String)>,
+    search_keys: Vec<&'a str>,
+    rest: Option<&'a str>,
+    // Its path iterator points to the remaining path parts.
+    url: Url,
+}
+
+impl<'a> Match<'a> {
+    pub fn new(pattern: &'a str, url: &Url) -> Option<Self> {
+        let Pattern {
+            segments,
+            search_keys,
+        } = Pattern::parse(pattern);
+        let mut url = url.clone();
+        let mut params = Vec::new();
+        let mut rest = None;
+
+        for segment in segments {
+            match segment {
+                Segment::Part(part) => {
+                    if url.next_path_part()? != part {
+                        return None;
+                    }
+                }
+                Segment::Param(name) => {
+                    let value = url.next_path_part()?.to_owned();
+                    params.push((name, value));
+                }
+                Segment::Rest(name) => {
+                    rest = Some(name);
+                    break;
+                }
+            }
+        }
+        if rest.is_none() && !url.clone().remaining_path_parts().is_empty() {
+            return None;
+        }
+        Some(Self {
+            params,
+            search_keys,
+            rest,
+            url,
+        })
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        if let Some((_, value)) = self.params.iter().find(|(param, _)| *param == name) {
+            return Some(value);
+        }
+        if self.search_keys.contains(&name) {
+            return self
+                .url
+                .search()
+                .get(name)
+                .and_then(|values| values.first())
+                .map(String::as_str);
+        }
+        None
+    }
+}
+
+// ------ Params ------
+
+/// Values of route fields written to a pattern - used by the macro `routes!`.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Params<'a> {
+    values: Vec<(&'a str, String)>,
+    rest: Option<Box<dyn FnOnce(Url) -> Url + 'a>>,
+}
+
+impl Params<'_> {
+    /// # Panics
+    ///
+    /// Panics when a path parameter in the `pattern` isn't bound to a field.
+    pub fn append_to(mut self, pattern: &str, mut url: Url) -> Url {
+        let Pattern {
+            segments,
+            search_keys,
+        } = Pattern::parse(pattern);
+
+        for segment in segments {
+            url = match segment {
+                Segment::Part(part) => url.add_path_part(part),
+                Segment::Param(name) => {
+                    let value = self
+                        .value(name)
+                        .unwrap_or_else(|| panic!("route parameter `{name}` has no value"));
+                    url.add_path_part(value)
+                }
+                Segment::Rest(_) => match self.rest.take() {
+                    Some(append_rest) => append_rest(url),
+                    None => url,
+                },
+            };
+        }
+        for key in search_keys {
+            if let Some(value) = self.value(key) {
+                url.search_mut().insert(key.to_owned(), vec![value]);
+            }
+        }
+        url
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.values
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+// ------ RouteParam ------
+
+/// A field of a route - used by the macro `routes!`.
+///
+/// `Marker` distinguishes required, optional and nested fields.
+#[doc(hidden)]
+pub trait RouteParam<Marker>: Sized {
+    fn from_match(matched: &Match, name: &str) -> Option<Self>;
+    fn write<'a>(&'a self, name: &'a str, params: &mut Params<'a>);
+}
+
+#[doc(hidden)]
+pub enum Required {}
+
+#[doc(hidden)]
+pub enum Optional {}
+
+#[doc(hidden)]
+pub enum Nested {}
+
+impl<T: FromStr + Display> RouteParam<Required> for T {
+    fn from_match(matched: &Match, name: &str) -> Option<Self> {
+        matched.value(name)?.parse().ok()
+    }
+
+    fn write<'a>(&'a self, name: &'a str, params: &mut Params<'a>) {
+        params.values.push((name, self.to_string()));
+    }
+}
+
+/// Invalid values are ignored - e.g. `?page=x` is parsed as `None` for `page: Option<u32>`.
+impl<T: FromStr + Display> RouteParam<Optional> for Option<T> {
+    fn from_match(matched: &Match, name: &str) -> Option<Self> {
+        Some(matched.value(name).and_then(|value| value.parse().ok()))
+    }
+
+    fn write<'a>(&'a self, name: &'a str, params: &mut Params<'a>) {
+        if let Some(value) = self {
+            params.values.push((name, value.to_string()));
+        }
+    }
+}
+
+impl<T: Routable> RouteParam<Nested> for T {
+    fn from_match(matched: &Match, name: &str) -> Option<Self> {
+        if matched.rest == Some(name) {
+            T::from_url(&matched.url)
+        } else {
+            None
+        }
+    }
+
+    fn write<'a>(&'a self, _: &'a str, params: &mut Params<'a>) {
+        params.rest = Some(Box::new(move |url| self.append_to(url)));
+    }
+}
+
+// ------ routes! ------
+
+/// Implement `Routable` for an enum - see the module [`router`](browser/router/index.html).
+///
+/// # Example
+///
+/// ```rust,ignore
+///routes! {
+///    Route {
+///        Home => "/",
+///        User { id } => "/users/:id",
+///        Admin(page) => "/admin/*page",
+///        NotFound => "/*",
+///    }
+///}
+/// ```
+#[macro_export]
+macro_rules! routes {
+    (
+        $route:ty {
+            $(
+                $variant:ident
+                $( { $( $field:ident ),* $(,)? } )?
+                $( ( $( $tuple_field:ident ),* $(,)? ) )?
+                => $pattern:literal
+            ),* $(,)?
+        }
+    ) => {
+        impl $crate::browser::router::Routable for $route {
+            fn from_url(url: &$crate::browser::Url) -> Option<Self> {
+                $(
+                    if let Some(matched) = $crate::browser::router::Match::new($pattern, url) {
+                        // Unit variants don't read parameters.
+                        #[allow(unused_variables)]
+                        let matched = &matched;
+                        #[allow(clippy::redundant_closure_call)]
+                        let route = (|| {
+                            Some(Self::$variant
+                                $( { $( $field: $crate::browser::router::RouteParam::from_match(
+                                    matched, stringify!($field)
+                                )? ),* } )?
+                                $( ( $( $crate::browser::router::RouteParam::from_match(
+                                    matched, stringify!($tuple_field)
+                                )? ),* ) )?
+                            )
+                        })();
+                        if route.is_some() {
+                            return route;
+                        }
+                    }
+                )*
+                None
+            }
+
+            fn append_to(&self, url: $crate::browser::Url) -> $crate::browser::Url {
+                #[allow(unused_mut)]
+                let mut params = $crate::browser::router::Params::default();
+                let pattern = match self {
+                    $(
+                        Self::$variant
+                        $( { $( $field ),* } )?
+                        $( ( $( $tuple_field ),* ) )?
+                        => {
+                            $( $( $crate::browser::router::RouteParam::write(
+                                $field, stringify!($field), &mut params
+                            ); )* )?
+                            $( $( $crate::browser::router::RouteParam::write(
+                                $tuple_field, stringify!($tuple_field), &mut params
+                            ); )* )?
+                            $pattern
+                        }
+                    )*
+                };
+                params.append_to(pattern, url)
+            }
+        }
+    };
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::UrlSearch;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Tab {
+        Comments,
+        History,
+    }
+
+    impl FromStr for Tab {
+        type Err = ();
+
+        fn from_str(tab: &str) -> Result<Self, Self::Err> {
+            match tab {
+                "comments" => Ok(Self::Comments),
+                "history" => Ok(Self::History),
+                _ => Err(()),
+            }
+        }
+    }
+
+    impl Display for Tab {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Self::Comments => write!(f, "comments"),
+                Self::History => write!(f, "history"),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum AdminRoute {
+        Dashboard,
+        Report { year: u16 },
+    }
+
+    crate::routes! {
+        AdminRoute {
+            Dashboard => "/",
+            Report { year } => "/report/:year",
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Route {
+        Home,
+        User {
+            id: u32,
+        },
+        Post {
+            id: u32,
+            post_id: u32,
+            tab: Option<Tab>,
+        },
+        Search {
+            query: String,
+        },
+        Admin(AdminRoute),
+        NotFound,
+    }
+
+    crate::routes! {
+        Route {
+            Home => "/",
+            User { id } => "/users/:id",
+            Post { id, post_id, tab } => "/users/:id/posts/:post_id?tab",
+            Search { query } => "/search?query",
+            Admin(page) => "/admin/*page",
+            NotFound => "/*",
+        }
+    }
+
+    fn url(path: &[&str]) -> Url {
+        Url::new().set_path(path)
+    }
+
+    #[test]
+    fn parse_routes() {
+        assert_eq!(Route::from_url(&url(&[])), Some(Route::Home));
+        assert_eq!(
+            Route::from_url(&url(&["users", "7"])),
+            Some(Route::User { id: 7 })
+        );
+        assert_eq!(
+            Route::from_url(
+                &url(&["users", "7", "posts", "3"])
+                    .set_search(UrlSearch::new(vec![("tab", vec!["history"])]))
+            ),
+            Some(Route::Post {
+                id: 7,
+                post_id: 3,
+                tab: Some(Tab::History)
+            })
+        );
+        assert_eq!(
+            Route::from_url(&url(&["users", "7", "posts", "3"])),
+            Some(Route::Post {
+                id: 7,
+                post_id: 3,
+                tab: None
+            })
+        );
+        assert_eq!(
+            Route::from_url(&url(&["admin", "report", "2024"])),
+            Some(Route::Admin(AdminRoute::Report { year: 2024 }))
+        );
+        assert_eq!(
+            Route::from_url(&url(&["admin"])),
+            Some(Route::Admin(AdminRoute::Dashboard))
+        );
+    }
+
+    #[test]
+    fn unmatched_routes_fall_through() {
+        // Invalid parameter.
+        assert_eq!(
+            Route::from_url(&url(&["users", "x"])),
+            Some(Route::NotFound)
+        );
+        // Missing required search parameter.
+        assert_eq!(Route::from_url(&url(&["search"])), Some(Route::NotFound));
+        // Unmatched nested route.
+        assert_eq!(
+            Route::from_url(&url(&["admin", "unknown"])),
+            Some(Route::NotFound)
+        );
+        assert_eq!(AdminRoute::from_url(&url(&["report"])), None);
+    }
+
+    #[test]
+    fn parse_remaining_path_parts() {
+        let mut url = url(&["app", "users", "7"]);
+        url.next_path_part();
+        assert_eq!(Route::from_url(&url), Some(Route::User { id: 7 }));
+    }
+
+    #[test]
+    fn routes_round_trip() {
+        let routes = [
+            Route::Home,
+            Route::User { id: 7 },
+            Route::Post {
+                id: 7,
+                post_id: 3,
+                tab: Some(Tab::Comments),
+            },
+            Route::Post {
+                id: 7,
+                post_id: 3,
+                tab: None,
+            },
+            Route::Search {
+                query: "seed".to_owned(),
+            },
+            Route::Admin(AdminRoute::Dashboard),
+            Route::Admin(AdminRoute::Report { year: 2024 }),
+        ];
+        for route in routes {
+            assert_eq!(Route::from_url(&route.to_url()), Some(route));
+        }
+    }
+
+    #[test]
+    fn append_to_base_url() {
+        let route = Route::Post {
+            id: 7,
+            post_id: 3,
+            tab: Some(Tab::Comments),
+        };
+        let url = route.append_to(url(&["app"]));
+        assert_eq!(url.path(), ["app", "users", "7", "posts", "3"]);
+        assert_eq!(url.search().get("tab"), Some(&vec!["comments".to_owned()]));
+
+        let url = Route::Admin(AdminRoute::Report { year: 2024 }).to_url();
+        assert_eq!(url.path(), ["admin", "report", "2024"]);
+    }
+}
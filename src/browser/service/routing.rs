@@ -1,42 +1,342 @@
 use super::super::{util, Url};
 use crate::{
-    app::{subs, Notification},
+    app::{
+        subs::{
+            self,
+            navigation_requested::{Decision, Navigation, NavigationKind},
+        },
+        Notification,
+    },
     browser::json,
 };
-use std::rc::Rc;
-use wasm_bindgen::{closure::Closure, JsCast};
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// Scroll positions are saved to `history.state` when the user stops scrolling for this time.
+const SAVE_SCROLL_POSITION_DELAY_MS: u32 = 100;
+
+thread_local! {
+    // `location.href` after the last navigation - a blocked back / forward navigation re-pushes it.
+    static CURRENT_HREF: RefCell<Option<String>> = const { RefCell::new(None) };
+    // Set while the scroll listener is active - i.e. in `ScrollRestoration::Automatic`.
+    static SAVE_SCROLL_POSITIONS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How the app reads and writes the URL - see `App::routing_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoutingMode {
+    /// Routes are paths changed by the History API (`push_state`) - e.g. `/admin/report`.
+    #[default]
+    History,
+    /// Routes are stored in the URL hash - e.g. `/#/admin/report`.
+    /// The server has to serve only the index page.
+    Hash,
+}
+
+/// Whether Seed manages the scroll position on navigation - see `App::scroll_restoration`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScrollRestoration {
+    /// Seed doesn't change the scroll position - the app (or the browser) manages it.
+    #[default]
+    Manual,
+    /// Seed saves scroll positions to `history.state` and restores them after the next render
+    /// on back / forward navigation. New routes are scrolled to the top
+    /// or to the element with the id from the url hash (in `RoutingMode::History`).
+    Automatic,
+}
+
+// ------ History state ------
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct ScrollPosition {
+    x: f64,
+    y: f64,
+}
+
+impl ScrollPosition {
+    fn current() -> Self {
+        let window = util::window();
+        Self {
+            x: window.scroll_x().unwrap_or_default(),
+            y: window.scroll_y().unwrap_or_default(),
+        }
+    }
+}
+
+/// Stored in `history.state` by `push_route`.
+#[derive(Serialize, Deserialize)]
+struct HistoryState {
+    url: Url,
+    scroll: Option<ScrollPosition>,
+}
+
+impl HistoryState {
+    /// `Url`s pushed by `Url::go_and_push` are stored without scroll positions.
+    fn from_js_value(state: &JsValue) -> Option<Self> {
+        json::from_js_value(state).ok().or_else(|| {
+            json::from_js_value(state)
+                .ok()
+                .map(|url| Self { url, scroll: None })
+        })
+    }
+
+    fn to_js_value(&self) -> JsValue {
+        json::to_js_value(self).expect("Problem serializing route data")
+    }
+}
+
+/// Save the current scroll position to `history.state` of the current entry.
+fn save_scroll_position() {
+    let history = util::history();
+    let url = history
+        .state()
+        .ok()
+        .and_then(|state| HistoryState::from_js_value(&state))
+        .map_or_else(Url::current, |state| state.url);
+    let state = HistoryState {
+        url,
+        scroll: Some(ScrollPosition::current()),
+    };
+    // It fails when the browser limits the number of calls - the position just isn't saved.
+    let _ = history.replace_state(&state.to_js_value(), "");
+}
+
+/// Save the scroll position of the current route before leaving it - only in `ScrollRestoration::Automatic`.
+fn save_scroll_position_before_navigation() {
+    if SAVE_SCROLL_POSITIONS.with(Cell::get) {
+        save_scroll_position();
+    }
+}
+
+/// Save the scroll position to `history.state` whenever the user stops scrolling.
+/// It also disables the browser's scroll restoration.
+pub fn setup_scroll_listener(updated_listener: impl Fn(Closure<dyn FnMut(web_sys::Event)>)) {
+    let mut timeout: Option<Timeout> = None;
+    let closure = Closure::new(move |_: web_sys::Event| {
+        // The previous timeout is cancelled on drop.
+        drop(timeout.replace(Timeout::new(
+            SAVE_SCROLL_POSITION_DELAY_MS,
+            save_scroll_position,
+        )));
+    });
+
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .add_event_listener_with_callback("scroll", closure.as_ref().unchecked_ref())
+        .expect("Problem adding scroll listener");
+    util::history()
+        .set_scroll_restoration(web_sys::ScrollRestoration::Manual)
+        .expect("Problem setting scroll restoration");
+    SAVE_SCROLL_POSITIONS.with(|save| save.set(true));
+
+    updated_listener(closure);
+}
+
+/// Remove the listener set up by `setup_scroll_listener` and enable the browser's scroll restoration.
+pub fn remove_scroll_listener(closure: &Closure<dyn FnMut(web_sys::Event)>) {
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .remove_event_listener_with_callback("scroll", closure.as_ref().unchecked_ref())
+        .expect("Problem removing scroll listener");
+    util::history()
+        .set_scroll_restoration(web_sys::ScrollRestoration::Auto)
+        .expect("Problem setting scroll restoration");
+    SAVE_SCROLL_POSITIONS.with(|save| save.set(false));
+}
+
+// ------ Scrolling ------
+
+#[derive(Debug, Clone)]
+pub(crate) enum ScrollTarget {
+    Top,
+    Position(ScrollPosition),
+    // The id of the element.
+    Element(String),
+}
+
+impl ScrollTarget {
+    fn for_new_route(url: &Url, mode: RoutingMode) -> Self {
+        match (mode, url.hash()) {
+            (RoutingMode::History, Some(hash)) if !hash.is_empty() => Self::Element(hash.clone()),
+            _ => Self::Top,
+        }
+    }
+}
+
+/// Notified by routing; the `App` scrolls after the next render (see `ScrollRestoration`).
+#[derive(Debug, Clone)]
+pub(crate) struct ScrollAfterRender(pub ScrollTarget);
+
+/// Notified by `Orders::guard_navigation`; the `App` asks the guards also before the page is unloaded.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct GuardBeforeunload;
+
+pub(crate) fn scroll_to(target: &ScrollTarget) {
+    let window = util::window();
+    match target {
+        ScrollTarget::Top => window.scroll_to_with_x_and_y(0., 0.),
+        ScrollTarget::Position(ScrollPosition { x, y }) => window.scroll_to_with_x_and_y(*x, *y),
+        ScrollTarget::Element(id) => match util::document().get_element_by_id(id) {
+            Some(element) => element.scroll_into_view(),
+            None => window.scroll_to_with_x_and_y(0., 0.),
+        },
+    }
+}
 
 /// Add a new route using history's `push_state` method.
 ///
+/// The scroll position of the current route is saved to its `history.state`
+/// in `ScrollRestoration::Automatic`.
+///
 /// # References
 /// * [MDN docs](https://developer.mozilla.org/en-US/docs/Web/API/History_API)
 pub fn push_route<U: Into<Url>>(url: U) -> Url {
     let url = url.into();
+    save_scroll_position_before_navigation();
     // We use data to evaluate the path instead of the path displayed in the url.
-    let data = json::to_js_value(&url).expect("Problem serializing route data");
+    let state = HistoryState {
+        url: url.clone(),
+        scroll: None,
+    };
 
     util::history()
-        .push_state_with_url(&data, "", Some(&url.to_string()))
+        .push_state_with_url(&state.to_js_value(), "", Some(&url.to_string()))
         .expect("Problem pushing state");
+    remember_current_url();
     url
 }
 
+/// Remember the current url as the one to return to when a back / forward navigation is blocked.
+pub(crate) fn remember_current_url() {
+    let href = util::window().location().href().ok();
+    CURRENT_HREF.with(|current_href| *current_href.borrow_mut() = href);
+}
+
+fn is_current_url_remembered() -> bool {
+    let href = util::window().location().href().ok();
+    CURRENT_HREF.with(|current_href| *current_href.borrow() == href)
+}
+
+fn restore_remembered_url() {
+    let href = CURRENT_HREF.with(|current_href| current_href.borrow().clone());
+    if let Some(url) = href.and_then(|href| href.parse::<Url>().ok()) {
+        push_route(url);
+    }
+}
+
+// ------ Navigation guards ------
+
+/// Notify `subs::NavigationRequested` (see `Orders::guard_navigation`) and return the decision.
+/// Returns `None` when a guard has deferred the navigation - `resume` is called once it's resolved.
+fn ask_guards(
+    url: &Url,
+    kind: NavigationKind,
+    notify: &dyn Fn(Notification),
+    resume: impl FnOnce(Decision) + 'static,
+) -> Option<Decision> {
+    let navigation = Navigation::new(url.clone(), kind);
+    notify(Notification::new(subs::NavigationRequested(
+        navigation.clone(),
+    )));
+    let decision = navigation.take_decision();
+    if decision.is_none() {
+        navigation.on_resolved(resume);
+    }
+    decision
+}
+
+/// Change the url and notify `subs::UrlChanged` unless the navigation has been blocked.
+fn complete_navigation(
+    decision: Decision,
+    url: Url,
+    mode: RoutingMode,
+    base_path: &[String],
+    notify: &dyn Fn(Notification),
+) {
+    match decision {
+        Decision::Allow => navigate(url, mode, base_path, notify),
+        Decision::Redirect(redirect_url) => navigate(redirect_url, mode, base_path, notify),
+        Decision::Block => (),
+    }
+}
+
+fn navigate(url: Url, mode: RoutingMode, base_path: &[String], notify: &dyn Fn(Notification)) {
+    let url = url.skip_base_path(base_path).skip_hash_base_path(&[]);
+    match mode {
+        RoutingMode::History => {
+            push_route(url.clone());
+            let scroll_target = ScrollTarget::for_new_route(&url, mode);
+            notify(Notification::new(subs::UrlChanged(url)));
+            notify(Notification::new(ScrollAfterRender(scroll_target)));
+        }
+        // `UrlChanged` is notified by the `hashchange` listener.
+        RoutingMode::Hash => {
+            save_scroll_position_before_navigation();
+            util::window()
+                .location()
+                .set_hash(url.hash().map_or("", String::as_str))
+                .expect("Problem setting location hash");
+            remember_current_url();
+        }
+    }
+}
+
+/// The browser has already changed the url - re-push the previous one
+/// when the navigation is blocked or deferred.
+fn guard_back_forward(
+    url: Url,
+    scroll: Option<ScrollPosition>,
+    mode: RoutingMode,
+    base_path: &Rc<[String]>,
+    notify: &Rc<dyn Fn(Notification)>,
+) {
+    let resume = {
+        let (url, base_path, notify) = (url.clone(), Rc::clone(base_path), Rc::clone(notify));
+        move |decision| complete_navigation(decision, url, mode, &base_path, &*notify)
+    };
+    match ask_guards(&url, NavigationKind::BackForward, &**notify, resume) {
+        Some(Decision::Allow) => {
+            remember_current_url();
+            notify(Notification::new(subs::UrlChanged(url)));
+            if let Some(scroll) = scroll {
+                notify(Notification::new(ScrollAfterRender(
+                    ScrollTarget::Position(scroll),
+                )));
+            }
+        }
+        Some(Decision::Redirect(redirect_url)) => {
+            navigate(redirect_url, mode, base_path, &**notify);
+        }
+        Some(Decision::Block) | None => restore_remembered_url(),
+    }
+}
+
 pub fn setup_popstate_listener(
     updated_listener: impl Fn(Closure<dyn FnMut(web_sys::Event)>) + 'static,
     notify: impl Fn(Notification) + 'static,
     base_path: Rc<[String]>,
 ) {
+    let notify: Rc<dyn Fn(Notification)> = Rc::new(notify);
     let closure = Closure::new(move |ev: web_sys::Event| {
         let ev = ev
             .dyn_ref::<web_sys::PopStateEvent>()
             .expect("Problem casting as Popstate event");
 
-        let url = json::from_js_value(&ev.state()).map_or_else(|_| Url::current(), |url| url);
+        let (url, scroll) = HistoryState::from_js_value(&ev.state())
+            .map_or_else(|| (Url::current(), None), |state| (state.url, state.scroll));
 
-        notify(Notification::new(subs::UrlChanged(
+        guard_back_forward(
             url.skip_base_path(&base_path),
-        )));
+            scroll,
+            RoutingMode::History,
+            &base_path,
+            &notify,
+        );
     });
+    remember_current_url();
 
     (util::window().as_ref() as &web_sys::EventTarget)
         .add_event_listener_with_callback("popstate", closure.as_ref().unchecked_ref())
@@ -45,22 +345,113 @@ pub fn setup_popstate_listener(
     updated_listener(closure);
 }
 
+/// Remove the listener set up by `setup_popstate_listener`.
+pub fn remove_popstate_listener(closure: &Closure<dyn FnMut(web_sys::Event)>) {
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .remove_event_listener_with_callback("popstate", closure.as_ref().unchecked_ref())
+        .expect("Problem removing popstate listener");
+}
+
+/// Notify `subs::UrlChanged` whenever the URL hash changes (used in `RoutingMode::Hash`).
+pub fn setup_hashchange_listener(
+    updated_listener: impl Fn(Closure<dyn FnMut(web_sys::Event)>) + 'static,
+    notify: impl Fn(Notification) + 'static,
+    base_path: Rc<[String]>,
+) {
+    let notify: Rc<dyn Fn(Notification)> = Rc::new(notify);
+    let closure = Closure::new(move |_: web_sys::Event| {
+        let url = Url::current().skip_base_path(&base_path);
+        // The hash has been changed by Seed - the navigation has been already guarded.
+        if is_current_url_remembered() {
+            notify(Notification::new(subs::UrlChanged(url)));
+            notify(Notification::new(ScrollAfterRender(ScrollTarget::Top)));
+            return;
+        }
+        let scroll = util::history()
+            .state()
+            .ok()
+            .and_then(|state| HistoryState::from_js_value(&state))
+            .and_then(|state| state.scroll);
+        guard_back_forward(url, scroll, RoutingMode::Hash, &base_path, &notify);
+    });
+    remember_current_url();
+
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .add_event_listener_with_callback("hashchange", closure.as_ref().unchecked_ref())
+        .expect("Problem adding hashchange listener");
+
+    updated_listener(closure);
+}
+
+/// Remove the listener set up by `setup_hashchange_listener`.
+pub fn remove_hashchange_listener(closure: &Closure<dyn FnMut(web_sys::Event)>) {
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .remove_event_listener_with_callback("hashchange", closure.as_ref().unchecked_ref())
+        .expect("Problem removing hashchange listener");
+}
+
+/// Ask navigation guards before the page is unloaded - see `NavigationKind::Unload`.
+pub fn setup_beforeunload_listener(
+    updated_listener: impl Fn(Closure<dyn FnMut(web_sys::Event)>) + 'static,
+    notify: impl Fn(Notification) + 'static,
+) {
+    let closure = Closure::new(move |event: web_sys::Event| {
+        let decision = ask_guards(&Url::current(), NavigationKind::Unload, &notify, |_| ());
+        if matches!(decision, Some(Decision::Allow)) {
+            return;
+        }
+        event.prevent_default();
+        // Chrome shows the confirmation dialog only when `returnValue` is set.
+        event
+            .unchecked_ref::<web_sys::BeforeUnloadEvent>()
+            .set_return_value("");
+    });
+
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .add_event_listener_with_callback("beforeunload", closure.as_ref().unchecked_ref())
+        .expect("Problem adding beforeunload listener");
+
+    updated_listener(closure);
+}
+
+/// Remove the listener set up by `setup_beforeunload_listener`.
+pub fn remove_beforeunload_listener(closure: &Closure<dyn FnMut(web_sys::Event)>) {
+    (util::window().as_ref() as &web_sys::EventTarget)
+        .remove_event_listener_with_callback("beforeunload", closure.as_ref().unchecked_ref())
+        .expect("Problem removing beforeunload listener");
+}
+
 #[allow(clippy::needless_pass_by_value)]
 pub fn url_request_handler(
     sub_data: subs::UrlRequested,
     base_path: Rc<[String]>,
+    mode: RoutingMode,
     notify: impl Fn(Notification) + 'static,
 ) {
     let subs::UrlRequested(url, request) = sub_data;
 
     match request.status() {
         subs::url_requested::UrlRequestStatus::Unhandled => {
-            if let Some(event) = request.event.borrow_mut().take() {
-                event.prevent_default(); // Prevent page refresh
+            // The browser follows the link - `beforeunload` guards are asked.
+            if request.is_external() {
+                return;
+            }
+            let kind = match request.event.borrow_mut().take() {
+                Some(event) => {
+                    event.prevent_default(); // Prevent page refresh
+                    NavigationKind::Link
+                }
+                None => NavigationKind::Request,
+            };
+            let notify: Rc<dyn Fn(Notification)> = Rc::new(notify);
+            let resume = {
+                let (url, base_path, notify) =
+                    (url.clone(), Rc::clone(&base_path), Rc::clone(&notify));
+                move |decision| complete_navigation(decision, url, mode, &base_path, &*notify)
+            };
+            if let Some(decision) = ask_guards(&url, kind, &*notify, resume) {
+                complete_navigation(decision, url, mode, &base_path, &*notify);
             }
-            let url = url.skip_base_path(&base_path).skip_hash_base_path(&[]);
-            push_route(url.clone());
-            notify(Notification::new(subs::UrlChanged(url)));
         }
         subs::url_requested::UrlRequestStatus::Handled(prevent_default) => {
             if prevent_default {
@@ -72,60 +463,316 @@ pub fn url_request_handler(
     }
 }
 
-/// Set up a listener that intercepts clicks on elements containing an Href
-/// attribute, so we can prevent page refresh for internal links, and route
-/// internally. Run this on load.
-#[allow(clippy::option_map_unit_fn)]
-pub fn setup_link_listener<F>(notify: F)
+/// Set up a listener that intercepts clicks on links, so we can prevent page refresh
+/// for internal links, and route internally. Run this on load.
+///
+/// - Only non-modified primary-button clicks on links without `download` and `target`
+///   (other than `_self`) are handled - the browser opens other links (e.g. in a new tab).
+/// - The `href` is resolved against `document.baseURI`. Same-origin links inside the `base_path`
+///   are internal, other links (and links with `rel="external"`) are external -
+///   see `subs::url_requested::UrlRequestKind`.
+/// - In `RoutingMode::Hash` only links that change just the hash of the current page are internal.
+///   In `RoutingMode::History` links to a fragment of the current page (e.g. `#section`)
+///   are left to the browser.
+///
+/// The returned closure has to be kept alive while the listener is attached;
+/// pass it to `remove_link_listener` to detach it.
+pub fn setup_link_listener<F>(
+    notify: F,
+    mode: RoutingMode,
+    base_path: Rc<[String]>,
+) -> Closure<dyn Fn(web_sys::Event)>
 where
     F: Fn(Notification) + 'static,
 {
-    let closure: Closure<dyn Fn(web_sys::Event) -> _> =
-        Closure::new(move |event: web_sys::Event| {
-            event
-                .target()
-                .and_then(|et| et.dyn_into::<web_sys::Element>().ok())
-                .and_then(|el| el.closest("a[href]").ok().flatten())
-                .and_then(|href_el| {
-                    if href_el.has_attribute("download") {
-                        None
-                    } else {
-                        href_el.get_attribute("href")
-                    }
-                })
-                // The first character being / or empty href indicates a rel link, which is what
-                // we're intercepting.
-                // @TODO: Resolve it properly, see Elm implementation:
-                // @TODO: https://github.com/elm/browser/blob/9f52d88b424dd12cab391195d5b090dd4639c3b0/src/Elm/Kernel/Browser.js#L157
-                .and_then(|href| {
-                    if href.is_empty() || href.starts_with('/') {
-                        Some(href)
-                    } else {
-                        None
-                    }
-                })
-                .map(|href| {
-                    // @TODO should be empty href ignored?
-                    if href.is_empty() {
-                        event.prevent_default(); // Prevent page refresh
-                    } else {
-                        // Only update when requested for an update by the user.
-                        let url: Url = href.parse().expect("cast link href to `Url`");
-
-                        notify(Notification::new(subs::UrlRequested(
-                            url,
-                            subs::url_requested::UrlRequest::new(
-                                subs::url_requested::UrlRequestStatus::default(),
-                                Some(event.clone()),
-                            ),
-                        )));
-                    }
-                });
-        });
+    let closure: Closure<dyn Fn(web_sys::Event)> = Closure::new(move |event: web_sys::Event| {
+        let Some((link, href)) = clicked_link(&event) else {
+            return;
+        };
+        if href.is_empty() {
+            event.prevent_default(); // Prevent page refresh
+            return;
+        }
+        let Some((url, kind)) = resolve_link(&link, &href, mode, &base_path) else {
+            return;
+        };
+        // Only update when requested for an update by the user.
+        notify(Notification::new(subs::UrlRequested(
+            url,
+            subs::url_requested::UrlRequest::new(
+                subs::url_requested::UrlRequestStatus::default(),
+                Some(event.clone()),
+                kind,
+            ),
+        )));
+    });
 
     (util::document().as_ref() as &web_sys::EventTarget)
         .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
         .expect("Problem setting up link interceptor");
 
-    closure.forget(); // todo: Can we store the closure somewhere to avoid using forget?
+    closure
+}
+
+/// Get the clicked link and its `href` - `None` when the browser should handle the click itself.
+fn clicked_link(event: &web_sys::Event) -> Option<(web_sys::Element, String)> {
+    if event.default_prevented() {
+        return None;
+    }
+    let mouse_event = event.dyn_ref::<web_sys::MouseEvent>()?;
+    if mouse_event.button() != 0
+        || mouse_event.ctrl_key()
+        || mouse_event.meta_key()
+        || mouse_event.shift_key()
+        || mouse_event.alt_key()
+    {
+        return None;
+    }
+    let link = event
+        .target()?
+        .dyn_into::<web_sys::Element>()
+        .ok()?
+        .closest("a[href]")
+        .ok()??;
+    if link.has_attribute("download") {
+        return None;
+    }
+    let opens_other_context = link.get_attribute("target").map_or(false, |target| {
+        !target.is_empty() && !target.eq_ignore_ascii_case("_self")
+    });
+    if opens_other_context {
+        return None;
+    }
+    let href = link.get_attribute("href")?;
+    Some((link, href))
+}
+
+/// Resolve the `href` against `document.baseURI` (like the browser does).
+/// Returns `None` for links that should be left to the browser.
+fn resolve_link(
+    link: &web_sys::Element,
+    href: &str,
+    mode: RoutingMode,
+    base_path: &[String],
+) -> Option<(Url, subs::url_requested::UrlRequestKind)> {
+    use subs::url_requested::UrlRequestKind;
+
+    let base_uri = util::document().base_uri().ok()??;
+    let resolved = web_sys::Url::new_with_base(href, &base_uri).ok()?;
+    let url = Url::from(&resolved);
+
+    let location = util::window().location();
+    let same_origin = Some(resolved.origin()) == location.origin().ok();
+    let same_document = same_origin
+        && Some(resolved.pathname()) == location.pathname().ok()
+        && Some(resolved.search()) == location.search().ok();
+    let rel_external = link.get_attribute("rel").map_or(false, |rel| {
+        rel.split_ascii_whitespace()
+            .any(|rel| rel.eq_ignore_ascii_case("external"))
+    });
+
+    let internal = same_origin
+        && !rel_external
+        && match mode {
+            RoutingMode::History => url.path().starts_with(base_path),
+            RoutingMode::Hash => same_document,
+        };
+    if !internal {
+        return Some((url, UrlRequestKind::External(resolved.href())));
+    }
+    if mode == RoutingMode::History && same_document && !resolved.hash().is_empty() {
+        return None;
+    }
+    Some((url, UrlRequestKind::Internal))
+}
+
+/// Remove the listener set up by `setup_link_listener`.
+pub fn remove_link_listener(closure: &Closure<dyn Fn(web_sys::Event)>) {
+    (util::document().as_ref() as &web_sys::EventTarget)
+        .remove_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+        .expect("Problem removing link interceptor");
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::subs::url_requested::UrlRequestKind;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Dispatch a click on a new link with the `attributes` and resolve it like the link listener.
+    fn click_link(
+        attributes: &[(&str, &str)],
+        init: &web_sys::MouseEventInit,
+    ) -> Option<(Url, UrlRequestKind)> {
+        let link = util::document().create_element("a").unwrap();
+        for (name, value) in attributes {
+            link.set_attribute(name, value).unwrap();
+        }
+        util::body().append_child(&link).unwrap();
+
+        let resolved = Rc::new(RefCell::new(None));
+        let listener = Closure::<dyn Fn(web_sys::Event)>::new({
+            let resolved = Rc::clone(&resolved);
+            move |event: web_sys::Event| {
+                *resolved.borrow_mut() = clicked_link(&event)
+                    .and_then(|(link, href)| resolve_link(&link, &href, RoutingMode::History, &[]));
+                // Don't let the browser follow the link.
+                event.prevent_default();
+            }
+        });
+        link.add_event_listener_with_callback("click", listener.as_ref().unchecked_ref())
+            .unwrap();
+
+        let event = web_sys::MouseEvent::new_with_mouse_event_init_dict(
+            "click",
+            init.clone().bubbles(true).cancelable(true),
+        )
+        .unwrap();
+        link.dispatch_event(&event).unwrap();
+        link.remove();
+        resolved.take()
+    }
+
+    fn click_link_with_href(href: &str) -> Option<(Url, UrlRequestKind)> {
+        click_link(&[("href", href)], &web_sys::MouseEventInit::new())
+    }
+
+    #[wasm_bindgen_test]
+    fn links_are_resolved_against_base_uri() {
+        let (url, kind) = click_link_with_href("/users/7?tab=posts").unwrap();
+        assert_eq!(kind, UrlRequestKind::Internal);
+        assert_eq!(url.path(), ["users", "7"]);
+        assert_eq!(url.search().get("tab"), Some(&vec!["posts".to_owned()]));
+
+        let base = Url::current().path().to_vec();
+        let (url, kind) = click_link_with_href("../x").unwrap();
+        assert_eq!(kind, UrlRequestKind::Internal);
+        assert_eq!(url.path().last().map(String::as_str), Some("x"));
+        assert!(url.path().len() <= base.len());
+
+        let origin = util::window().location().origin().unwrap();
+        let (url, kind) = click_link_with_href(&format!("{origin}/absolute")).unwrap();
+        assert_eq!(kind, UrlRequestKind::Internal);
+        assert_eq!(url.path(), ["absolute"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn external_links_are_requested_as_external() {
+        let (_, kind) = click_link_with_href("https://example.com/page").unwrap();
+        assert_eq!(
+            kind,
+            UrlRequestKind::External("https://example.com/page".to_owned())
+        );
+
+        let (_, kind) = click_link(
+            &[("href", "/page"), ("rel", "noopener external")],
+            &web_sys::MouseEventInit::new(),
+        )
+        .unwrap();
+        assert!(matches!(kind, UrlRequestKind::External(_)));
+    }
+
+    #[wasm_bindgen_test]
+    fn modified_and_new_context_clicks_are_ignored() {
+        assert!(click_link(
+            &[("href", "/page")],
+            web_sys::MouseEventInit::new().ctrl_key(true)
+        )
+        .is_none());
+        assert!(click_link(
+            &[("href", "/page")],
+            web_sys::MouseEventInit::new().meta_key(true)
+        )
+        .is_none());
+        assert!(click_link(
+            &[("href", "/page")],
+            web_sys::MouseEventInit::new().shift_key(true)
+        )
+        .is_none());
+        assert!(click_link(
+            &[("href", "/page")],
+            web_sys::MouseEventInit::new().button(1)
+        )
+        .is_none());
+        assert!(click_link(
+            &[("href", "/page"), ("target", "_blank")],
+            &web_sys::MouseEventInit::new()
+        )
+        .is_none());
+        assert!(click_link(
+            &[("href", "/page"), ("download", "")],
+            &web_sys::MouseEventInit::new()
+        )
+        .is_none());
+        assert!(click_link(
+            &[("href", "/page"), ("target", "_self")],
+            &web_sys::MouseEventInit::new()
+        )
+        .is_some());
+        // In-page anchors are left to the browser.
+        assert!(click_link_with_href("#section").is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn push_route_saves_scroll_position_of_current_entry() {
+        let previous_url = Url::current();
+        push_route(Url::new().set_path(["scroll", "first"]));
+        push_route(Url::new().set_path(["scroll", "second"]));
+
+        let state = HistoryState::from_js_value(&util::history().state().unwrap()).unwrap();
+        assert_eq!(state.url.path(), ["scroll", "second"]);
+        assert!(state.scroll.is_none());
+
+        // Plain `Url`s are stored by `Url::go_and_push`.
+        let url_state = json::to_js_value(&Url::new().set_path(["plain"])).unwrap();
+        let state = HistoryState::from_js_value(&url_state).unwrap();
+        assert_eq!(state.url.path(), ["plain"]);
+        assert!(state.scroll.is_none());
+
+        save_scroll_position();
+        let state = HistoryState::from_js_value(&util::history().state().unwrap()).unwrap();
+        assert_eq!(state.url.path(), ["scroll", "second"]);
+        assert!(state.scroll.is_some());
+
+        push_route(previous_url);
+    }
+
+    #[wasm_bindgen_test]
+    fn scroll_position_is_saved_only_in_automatic_mode() {
+        let previous_url = Url::current();
+        push_route(Url::new().set_path(["scroll", "manual"]));
+
+        save_scroll_position_before_navigation();
+        let state = HistoryState::from_js_value(&util::history().state().unwrap()).unwrap();
+        assert!(state.scroll.is_none());
+
+        SAVE_SCROLL_POSITIONS.with(|save| save.set(true));
+        save_scroll_position_before_navigation();
+        SAVE_SCROLL_POSITIONS.with(|save| save.set(false));
+        let state = HistoryState::from_js_value(&util::history().state().unwrap()).unwrap();
+        assert!(state.scroll.is_some());
+
+        push_route(previous_url);
+    }
+
+    #[wasm_bindgen_test]
+    fn new_routes_scroll_to_hash_element_in_history_mode() {
+        let url = Url::new().set_path(["docs"]).set_hash("install");
+        assert!(matches!(
+            ScrollTarget::for_new_route(&url, RoutingMode::History),
+            ScrollTarget::Element(id) if id == "install"
+        ));
+        assert!(matches!(
+            ScrollTarget::for_new_route(&url, RoutingMode::Hash),
+            ScrollTarget::Top
+        ));
+        assert!(matches!(
+            ScrollTarget::for_new_route(&Url::new(), RoutingMode::History),
+            ScrollTarget::Top
+        ));
+    }
 }
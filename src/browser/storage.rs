@@ -0,0 +1,355 @@
+//! Typed [Web Storage API](https://developer.mozilla.org/en-US/docs/Web/API/Web_Storage_API)
+//! - values are serialized to JSON by `serde`.
+//!
+//! Enable the crate feature `serde` (it's enabled also by `routing` and `fetch`).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use seed::browser::storage::{self, LocalStorage, SessionStorage, Storage};
+//!
+//! LocalStorage::set("user", &user)?;
+//! let user: User = LocalStorage::get("user")?;
+//!
+//! match SessionStorage::get::<Draft>("draft") {
+//!     Ok(draft) => ...,
+//!     Err(storage::StorageError::KeyNotFound(_)) => ...,
+//!     Err(error) => ...,
+//! }
+//! ```
+
+use super::{json, util::window};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fmt, marker::PhantomData, ops::Deref};
+use wasm_bindgen::{JsCast, JsValue};
+
+pub type Result<T> = std::result::Result<T, StorageError>;
+
+// ------ StorageError ------
+
+#[derive(Debug)]
+pub enum StorageError {
+    /// The storage isn't accessible - e.g. it's disabled by the browser settings.
+    Unavailable(JsValue),
+    /// There is no item with the given key.
+    KeyNotFound(String),
+    /// The item couldn't be saved because the storage is full.
+    QuotaExceeded(JsValue),
+    /// The value couldn't be serialized.
+    Encode(JsValue),
+    /// The item isn't valid JSON or the JSON doesn't match the target type.
+    Decode(JsValue),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unavailable(error) => write!(f, "storage is unavailable: {error:?}"),
+            Self::KeyNotFound(key) => write!(f, "storage item `{key}` not found"),
+            Self::QuotaExceeded(error) => write!(f, "storage quota exceeded: {error:?}"),
+            Self::Encode(error) => write!(f, "storage value couldn't be encoded: {error:?}"),
+            Self::Decode(error) => write!(f, "storage item couldn't be decoded: {error:?}"),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl StorageError {
+    fn from_set_item_error(error: JsValue) -> Self {
+        let quota_exceeded = error
+            .dyn_ref::<web_sys::DomException>()
+            .map_or(false, |exception| {
+                // Firefox uses a different name.
+                matches!(
+                    exception.name().as_str(),
+                    "QuotaExceededError" | "NS_ERROR_DOM_QUOTA_REACHED"
+                )
+            });
+        if quota_exceeded {
+            Self::QuotaExceeded(error)
+        } else {
+            Self::Unavailable(error)
+        }
+    }
+}
+
+// ------ Storage ------
+
+/// Typed access to a `web_sys::Storage` - see `LocalStorage` and `SessionStorage`.
+pub trait Storage {
+    /// The underlying `web_sys::Storage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` when the storage isn't accessible.
+    fn raw() -> Result<web_sys::Storage>;
+
+    /// Get the item with the `key` deserialized to `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable`, `StorageError::KeyNotFound` or `StorageError::Decode`.
+    fn get<T: DeserializeOwned>(key: &str) -> Result<T> {
+        let json = Self::get_raw(key)?;
+        let value = js_sys::JSON::parse(&json).map_err(StorageError::Decode)?;
+        json::from_js_value(&value).map_err(StorageError::Decode)
+    }
+
+    /// Get the item with the `key` as a string without deserialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` or `StorageError::KeyNotFound`.
+    fn get_raw(key: &str) -> Result<String> {
+        Self::raw()?
+            .get_item(key)
+            .map_err(StorageError::Unavailable)?
+            .ok_or_else(|| StorageError::KeyNotFound(key.to_owned()))
+    }
+
+    /// Serialize the `value` and save it under the `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable`, `StorageError::QuotaExceeded` or `StorageError::Encode`.
+    fn set<T: Serialize + ?Sized>(key: &str, value: &T) -> Result<()> {
+        let json = json::to_js_value(value)
+            .and_then(|value| js_sys::JSON::stringify(&value))
+            .map_err(StorageError::Encode)?;
+        Self::set_raw(key, &String::from(json))
+    }
+
+    /// Save the `value` under the `key` without serialization.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable` or `StorageError::QuotaExceeded`.
+    fn set_raw(key: &str, value: &str) -> Result<()> {
+        Self::raw()?
+            .set_item(key, value)
+            .map_err(StorageError::from_set_item_error)
+    }
+
+    /// Remove the item with the `key` (if any).
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable`.
+    fn remove(key: &str) -> Result<()> {
+        Self::raw()?
+            .remove_item(key)
+            .map_err(StorageError::Unavailable)
+    }
+
+    /// Remove all items.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable`.
+    fn clear() -> Result<()> {
+        Self::raw()?.clear().map_err(StorageError::Unavailable)
+    }
+}
+
+/// [`window.localStorage`](https://developer.mozilla.org/en-US/docs/Web/API/Window/localStorage)
+/// - items are shared by all tabs of the same origin and survive browser restarts.
+#[derive(Debug, Clone, Copy)]
+pub struct LocalStorage;
+
+impl Storage for LocalStorage {
+    fn raw() -> Result<web_sys::Storage> {
+        window()
+            .local_storage()
+            .map_err(StorageError::Unavailable)?
+            .ok_or(StorageError::Unavailable(JsValue::NULL))
+    }
+}
+
+/// [`window.sessionStorage`](https://developer.mozilla.org/en-US/docs/Web/API/Window/sessionStorage)
+/// - items are bound to the tab and survive page reloads.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionStorage;
+
+impl Storage for SessionStorage {
+    fn raw() -> Result<web_sys::Storage> {
+        window()
+            .session_storage()
+            .map_err(StorageError::Unavailable)?
+            .ok_or(StorageError::Unavailable(JsValue::NULL))
+    }
+}
+
+// ------ Persisted ------
+
+/// A value saved to the storage `S` under its key whenever it's changed through `update`.
+///
+/// # Example
+///
+/// ```rust,ignore
+///struct Model {
+///    settings: Persisted<Settings>,
+///}
+///
+///fn init(_: Url, _: &mut impl Orders<Msg>) -> Model {
+///    Model {
+///        settings: Persisted::load("settings"),
+///    }
+///}
+///
+///fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg>) {
+///    match msg {
+///        Msg::ToggleDarkMode => {
+///            model.settings.update(|settings| settings.dark_mode = !settings.dark_mode)
+///                .expect("save settings");
+///        }
+///    }
+///}
+/// ```
+pub struct Persisted<T, S: Storage = LocalStorage> {
+    key: String,
+    value: T,
+    // JSON of the saved value - the value isn't saved again when it hasn't changed.
+    saved_json: Option<String>,
+    storage: PhantomData<S>,
+}
+
+impl<T: Serialize + DeserializeOwned + Default, S: Storage> Persisted<T, S> {
+    /// Load the value saved under the `key` or use `T::default()`
+    /// when there is no valid saved value.
+    pub fn load(key: impl Into<String>) -> Self {
+        let key = key.into();
+        let saved = S::get_raw(&key).ok().and_then(|json| {
+            let value = js_sys::JSON::parse(&json).ok()?;
+            let value = json::from_js_value(&value).ok()?;
+            Some((value, json))
+        });
+        let (value, saved_json) = match saved {
+            Some((value, json)) => (value, Some(json)),
+            None => (T::default(), None),
+        };
+        Self {
+            key,
+            value,
+            saved_json,
+            storage: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize, S: Storage> Persisted<T, S> {
+    /// Change the value and save it when its JSON differs from the saved one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable`, `StorageError::QuotaExceeded` or `StorageError::Encode`.
+    /// The value is changed even if it couldn't be saved.
+    pub fn update(&mut self, f: impl FnOnce(&mut T)) -> Result<()> {
+        f(&mut self.value);
+        self.save_if_changed()
+    }
+
+    /// Save the value when its JSON differs from the saved one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StorageError::Unavailable`, `StorageError::QuotaExceeded` or `StorageError::Encode`.
+    pub fn save_if_changed(&mut self) -> Result<()> {
+        let json = json::to_js_value(&self.value)
+            .and_then(|value| js_sys::JSON::stringify(&value))
+            .map_err(StorageError::Encode)?;
+        let json = String::from(json);
+        if self.saved_json.as_ref() != Some(&json) {
+            S::set_raw(&self.key, &json)?;
+            self.saved_json = Some(json);
+        }
+        Ok(())
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, S: Storage> Deref for Persisted<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Debug, S: Storage> fmt::Debug for Persisted<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Persisted")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct Settings {
+        dark_mode: bool,
+    }
+
+    #[wasm_bindgen_test]
+    fn set_get_remove() {
+        let settings = Settings { dark_mode: true };
+        LocalStorage::set("seed-test-settings", &settings).unwrap();
+        assert_eq!(
+            LocalStorage::get::<Settings>("seed-test-settings").unwrap(),
+            settings
+        );
+
+        LocalStorage::remove("seed-test-settings").unwrap();
+        assert!(matches!(
+            LocalStorage::get::<Settings>("seed-test-settings"),
+            Err(StorageError::KeyNotFound(key)) if key == "seed-test-settings"
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_item_is_decode_error() {
+        SessionStorage::set_raw("seed-test-invalid", "{").unwrap();
+        assert!(matches!(
+            SessionStorage::get::<Settings>("seed-test-invalid"),
+            Err(StorageError::Decode(_))
+        ));
+
+        SessionStorage::set_raw("seed-test-invalid", r#"{"dark_mode": 1}"#).unwrap();
+        assert!(matches!(
+            SessionStorage::get::<Settings>("seed-test-invalid"),
+            Err(StorageError::Decode(_))
+        ));
+        SessionStorage::clear().unwrap();
+    }
+
+    #[wasm_bindgen_test]
+    fn persisted_saves_changes() {
+        SessionStorage::remove("seed-test-persisted").unwrap();
+        let mut settings = Persisted::<Settings, SessionStorage>::load("seed-test-persisted");
+        assert_eq!(*settings, Settings::default());
+
+        settings
+            .update(|settings| settings.dark_mode = true)
+            .unwrap();
+        let loaded = Persisted::<Settings, SessionStorage>::load("seed-test-persisted");
+        assert!(loaded.dark_mode);
+        SessionStorage::remove("seed-test-persisted").unwrap();
+    }
+}
@@ -3,10 +3,15 @@ use std::{borrow::Cow, collections::BTreeMap, fmt, str::FromStr};
 use wasm_bindgen::JsValue;
 
 #[cfg(feature = "routing")]
-use crate::browser::json;
+use crate::browser::{json, service::routing};
 #[cfg(feature = "routing")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "serde")]
+mod typed_search;
+#[cfg(feature = "serde")]
+pub use typed_search::UrlSearchError;
+
 pub const DUMMY_BASE_URL: &str = "http://example.com";
 
 // ------ Url ------
@@ -51,6 +56,7 @@ impl Url {
         util::history()
             .push_state_with_url(&data, "", Some(&self.to_string()))
             .expect("Problem pushing state");
+        routing::remember_current_url();
     }
 
     /// Change the browser URL, but do not trigger a page load.
@@ -68,6 +74,7 @@ impl Url {
         util::history()
             .replace_state_with_url(&data, "", Some(&self.to_string()))
             .expect("Problem pushing state");
+        routing::remember_current_url();
     }
 
     /// Creates a new `Url` from the one that is currently set in the browser.
@@ -288,6 +295,8 @@ impl Url {
     /// ])
     /// ```
     ///
+    /// Typed search parameters can be set from a struct by `Url::set_search_struct`.
+    ///
     /// # References
     /// * [MDN docs](https://developer.mozilla.org/en-US/docs/Web/API/URL/search)
     #[must_use]
@@ -296,6 +305,25 @@ impl Url {
         self
     }
 
+    /// Sets search from a struct and returns updated `Url` - see `UrlSearch::from_struct`.
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// Url::new().set_search_struct(&model.filter)?
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `UrlSearchError` when the struct can't be written to the search.
+    #[cfg(feature = "serde")]
+    pub fn set_search_struct<T: serde::Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<Self, UrlSearchError> {
+        Ok(self.set_search(UrlSearch::from_struct(value)?))
+    }
+
     /// Get path.
     ///
     /// # References
@@ -0,0 +1,1090 @@
+//! Serde mapping between `UrlSearch` and typed structs - see `UrlSearch::from_struct`
+//! and `UrlSearch::to_struct`.
+
+use super::UrlSearch;
+use serde::{
+    de::{self, value::BorrowedStrDeserializer, DeserializeOwned},
+    ser::{self, Impossible, Serialize},
+};
+use std::{collections::BTreeMap, fmt, slice, str::FromStr};
+
+/// Joins the field name of a nested struct (or map) with the names of its fields - e.g. `price.min`.
+const KEY_SEPARATOR: char = '.';
+
+type Result<T> = std::result::Result<T, UrlSearchError>;
+
+fn join_key(prefix: Option<&str>, name: &str) -> String {
+    prefix.map_or_else(
+        || name.to_owned(),
+        |prefix| format!("{prefix}{KEY_SEPARATOR}{name}"),
+    )
+}
+
+// ------ UrlSearchError ------
+
+/// `UrlSearch` couldn't be mapped from or to a struct.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlSearchError {
+    key: Option<String>,
+    message: String,
+}
+
+impl UrlSearchError {
+    /// The search key of the invalid field (nested keys are joined by `.`).
+    /// It's `None` when the error isn't related to a single field -
+    /// e.g. a required field of the top-level struct is missing.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Set the full key of the field unless the error already has one.
+    fn at_key(mut self, key: &str) -> Self {
+        if self.key.is_none() {
+            self.key = Some(key.to_owned());
+        }
+        self
+    }
+
+    /// Prefix the key by the name of the parent field while the error is passed up.
+    fn in_field(mut self, name: &str) -> Self {
+        self.key = Some(match self.key {
+            Some(key) => join_key(Some(name), &key),
+            None => name.to_owned(),
+        });
+        self
+    }
+
+    fn unsupported(what: &str) -> Self {
+        Self {
+            key: None,
+            message: format!("{what} aren't supported in the search"),
+        }
+    }
+}
+
+impl fmt::Display for UrlSearchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "search parameter `{key}`: {}", self.message),
+            None => write!(f, "search: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for UrlSearchError {}
+
+impl ser::Error for UrlSearchError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Self {
+            key: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl de::Error for UrlSearchError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Self {
+            key: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+// ------ UrlSearch ------
+
+impl UrlSearch {
+    /// Makes a new `UrlSearch` from the fields of `value` - a struct or a map.
+    ///
+    /// - Numbers, `bool`s, `char`s and strings are written by `Display`.
+    /// - `None` and empty sequences are skipped.
+    /// - Sequences (e.g. `Vec`) are written as repeated keys - `tag=new&tag=sale`.
+    /// - Fields of nested structs and maps are prefixed by the field name - `price.min=10`.
+    /// - Unit enum variants are written by their (serde) names.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UrlSearchError` when `value` isn't a struct or a map, or when a field type
+    /// can't be written (e.g. nested sequences or enum variants with data).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// #[derive(Serialize, Deserialize)]
+    /// #[serde(rename_all = "snake_case")]
+    /// enum Sort { Newest, Price }
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Filter {
+    ///     query: Option<String>,
+    ///     #[serde(default)]
+    ///     tag: Vec<String>,
+    ///     sort: Sort,
+    /// }
+    ///
+    /// // `?query=shoes&sort=price&tag=new&tag=sale`
+    /// let url = Url::new().set_search_struct(&filter)?;
+    /// let filter: Filter = url.search().to_struct()?;
+    /// ```
+    pub fn from_struct<T: Serialize + ?Sized>(value: &T) -> Result<Self> {
+        let mut search = BTreeMap::new();
+        value.serialize(ValueSerializer {
+            search: &mut search,
+            key: None,
+        })?;
+        Ok(Self {
+            search,
+            invalid_components: Vec::new(),
+        })
+    }
+
+    /// Read a struct (or a map) from the search - see `UrlSearch::from_struct` for the mapping.
+    ///
+    /// - Values are parsed by the field types (`bool`s are `true` or `false`).
+    /// - A missing key or an empty value (`query=`) is `None`. Use `#[serde(default)]`
+    ///   for other fields that may be missing - e.g. `Vec`s (empty sequences aren't written).
+    /// - Unknown keys are ignored (unless `#[serde(deny_unknown_fields)]` is used).
+    /// - Invalid components (see `UrlSearch::invalid_components`) are read from their original values
+    ///   and they are kept in the `UrlSearch`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UrlSearchError` with the key of the field that couldn't be parsed
+    /// (e.g. `page=abc` for a number), has more values than expected, or is missing.
+    pub fn to_struct<T: DeserializeOwned>(&self) -> Result<T> {
+        let root = SearchNode::from_search(&self.search);
+        T::deserialize(ValueDeserializer {
+            values: root.values,
+            children: Some(&root.children),
+        })
+    }
+}
+
+// ------ ------ Serialization ------ ------
+
+// ------ ValueSerializer ------
+
+/// Writes a value into `search` at the `key` (`None` for the top-level struct).
+struct ValueSerializer<'a> {
+    search: &'a mut BTreeMap<String, Vec<String>>,
+    key: Option<String>,
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn push(self, value: String) -> Result<()> {
+        let key = self.key.ok_or_else(expected_struct)?;
+        self.search.entry(key).or_default().push(value);
+        Ok(())
+    }
+
+    fn sequence(self) -> Result<SeqSerializer<'a>> {
+        let key = self.key.ok_or_else(expected_struct)?;
+        Ok(SeqSerializer {
+            search: self.search,
+            key,
+            values: Vec::new(),
+        })
+    }
+
+    fn structure(self) -> StructSerializer<'a> {
+        StructSerializer {
+            search: self.search,
+            prefix: self.key,
+            next_key: None,
+        }
+    }
+}
+
+fn expected_struct() -> UrlSearchError {
+    ser::Error::custom("expected a struct or a map")
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+    type Ok = ();
+    type Error = UrlSearchError;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), UrlSearchError>;
+    type SerializeMap = StructSerializer<'a>;
+    type SerializeStruct = StructSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), UrlSearchError>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.push(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.push(v.to_owned())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<()> {
+        Err(UrlSearchError::unsupported("byte arrays"))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<()> {
+        self.push(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<()> {
+        Err(UrlSearchError::unsupported("enum variants with data"))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.sequence()
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.sequence()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(UrlSearchError::unsupported("enum variants with data"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(self.structure())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Ok(self.structure())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(UrlSearchError::unsupported("enum variants with data"))
+    }
+}
+
+// ------ SeqSerializer ------
+
+/// Writes sequence items as repeated keys.
+struct SeqSerializer<'a> {
+    search: &'a mut BTreeMap<String, Vec<String>>,
+    key: String,
+    values: Vec<String>,
+}
+
+impl SeqSerializer<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let value = value
+            .serialize(ScalarSerializer)
+            .map_err(|error| error.at_key(&self.key))?;
+        self.values.extend(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        if !self.values.is_empty() {
+            self.search.entry(self.key).or_default().extend(self.values);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = UrlSearchError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        SeqSerializer::end(self)
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = UrlSearchError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        SeqSerializer::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = UrlSearchError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<()> {
+        SeqSerializer::end(self)
+    }
+}
+
+// ------ StructSerializer ------
+
+/// Writes fields (or map entries) at keys prefixed by the key of the struct.
+struct StructSerializer<'a> {
+    search: &'a mut BTreeMap<String, Vec<String>>,
+    prefix: Option<String>,
+    next_key: Option<String>,
+}
+
+impl StructSerializer<'_> {
+    fn serialize_value_at<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<()> {
+        value
+            .serialize(ValueSerializer {
+                search: self.search,
+                key: Some(key.clone()),
+            })
+            .map_err(|error| error.at_key(&key))
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer<'_> {
+    type Ok = ();
+    type Error = UrlSearchError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let key = join_key(self.prefix.as_deref(), name);
+        self.serialize_value_at(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for StructSerializer<'_> {
+    type Ok = ();
+    type Error = UrlSearchError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<()> {
+        let name = key
+            .serialize(ScalarSerializer)?
+            .ok_or_else(|| ser::Error::custom("map keys can't be empty"))?;
+        self.next_key = Some(join_key(self.prefix.as_deref(), &name));
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<()> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value without a key"))?;
+        self.serialize_value_at(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// ------ ScalarSerializer ------
+
+/// Writes a single value - a sequence item or a map key. `None` is skipped.
+struct ScalarSerializer;
+
+fn nested_compound() -> UrlSearchError {
+    UrlSearchError::unsupported("nested sequences, structs and maps in sequences and map keys")
+}
+
+impl ser::Serializer for ScalarSerializer {
+    type Ok = Option<String>;
+    type Error = UrlSearchError;
+    type SerializeSeq = Impossible<Option<String>, UrlSearchError>;
+    type SerializeTuple = Impossible<Option<String>, UrlSearchError>;
+    type SerializeTupleStruct = Impossible<Option<String>, UrlSearchError>;
+    type SerializeTupleVariant = Impossible<Option<String>, UrlSearchError>;
+    type SerializeMap = Impossible<Option<String>, UrlSearchError>;
+    type SerializeStruct = Impossible<Option<String>, UrlSearchError>;
+    type SerializeStructVariant = Impossible<Option<String>, UrlSearchError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Option<String>> {
+        Ok(Some(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Option<String>> {
+        Ok(Some(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Option<String>> {
+        Err(UrlSearchError::unsupported("byte arrays"))
+    }
+
+    fn serialize_none(self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Option<String>> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Option<String>> {
+        Ok(Some(variant.to_owned()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Option<String>> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Option<String>> {
+        Err(UrlSearchError::unsupported("enum variants with data"))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(nested_compound())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple> {
+        Err(nested_compound())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(nested_compound())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(UrlSearchError::unsupported("enum variants with data"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(nested_compound())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct> {
+        Err(nested_compound())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(UrlSearchError::unsupported("enum variants with data"))
+    }
+}
+
+// ------ ------ Deserialization ------ ------
+
+// ------ SearchNode ------
+
+/// Search entries grouped by the parts of their keys - `price.min` is the child `min` of `price`.
+#[derive(Default)]
+struct SearchNode<'a> {
+    values: &'a [String],
+    children: BTreeMap<&'a str, SearchNode<'a>>,
+}
+
+impl<'a> SearchNode<'a> {
+    fn from_search(search: &'a BTreeMap<String, Vec<String>>) -> Self {
+        let mut root = Self::default();
+        for (key, values) in search {
+            let node = key.split(KEY_SEPARATOR).fold(&mut root, |node, part| {
+                node.children.entry(part).or_default()
+            });
+            node.values = values;
+        }
+        root
+    }
+}
+
+// ------ ValueDeserializer ------
+
+/// Reads a value from search values and / or from nested keys (`children`).
+struct ValueDeserializer<'a> {
+    values: &'a [String],
+    children: Option<&'a BTreeMap<&'a str, SearchNode<'a>>>,
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn has_children(&self) -> bool {
+        self.children.map_or(false, |children| !children.is_empty())
+    }
+
+    /// A missing key or an empty value (`query=`) - e.g. an empty form input.
+    fn is_none(&self) -> bool {
+        !self.has_children() && self.values.len() <= 1 && self.values.iter().all(String::is_empty)
+    }
+
+    fn scalar(&self) -> Result<&'a str> {
+        match self.values {
+            [value] if !self.has_children() => Ok(value),
+            [] if self.has_children() => {
+                Err(de::Error::custom("expected a value, found nested keys"))
+            }
+            [] => Err(de::Error::custom("expected a value")),
+            values => Err(de::Error::custom(format!(
+                "expected a single value, found {}",
+                values.len()
+            ))),
+        }
+    }
+
+    fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.scalar()?;
+        value
+            .parse()
+            .map_err(|error| de::Error::custom(format!("invalid value `{value}`: {error}")))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'a> de::Deserializer<'a> for ValueDeserializer<'a> {
+    type Error = UrlSearchError;
+
+    fn deserialize_any<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        if self.has_children() {
+            return self.deserialize_map(visitor);
+        }
+        match self.values {
+            [] => visitor.visit_unit(),
+            [value] => visitor.visit_borrowed_str(value),
+            _ => self.deserialize_seq(visitor),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_str(self.scalar()?)
+    }
+
+    fn deserialize_string<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_bytes(self.scalar()?.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        if self.is_none() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'a>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'a>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        if self.values.is_empty() && self.has_children() {
+            return Err(de::Error::custom("expected values, found nested keys"));
+        }
+        visitor.visit_seq(SeqAccess {
+            values: self.values.iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'a>>(self, _: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'a>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        if !self.values.is_empty() {
+            return Err(de::Error::custom(format!(
+                "expected nested keys (`name{KEY_SEPARATOR}field`), found a value"
+            )));
+        }
+        visitor.visit_map(MapAccess {
+            children: self.children.into_iter().flatten(),
+            key: None,
+            next_value: None,
+        })
+    }
+
+    fn deserialize_struct<V: de::Visitor<'a>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: de::Visitor<'a>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_enum(BorrowedStrDeserializer::new(self.scalar()?))
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+// ------ SeqAccess ------
+
+/// Reads values of a repeated key.
+struct SeqAccess<'a> {
+    values: slice::Iter<'a, String>,
+}
+
+impl<'a> de::SeqAccess<'a> for SeqAccess<'a> {
+    type Error = UrlSearchError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'a>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>> {
+        self.values
+            .next()
+            .map(|value| {
+                seed.deserialize(ValueDeserializer {
+                    values: slice::from_ref(value),
+                    children: None,
+                })
+            })
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+// ------ MapAccess ------
+
+/// Reads fields of a struct (or entries of a map) from nested keys.
+struct MapAccess<'a, I> {
+    children: I,
+    key: Option<&'a str>,
+    next_value: Option<&'a SearchNode<'a>>,
+}
+
+impl<'a, I> de::MapAccess<'a> for MapAccess<'a, I>
+where
+    I: Iterator<Item = (&'a &'a str, &'a SearchNode<'a>)>,
+{
+    type Error = UrlSearchError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'a>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some((name, node)) = self.children.next() else {
+            return Ok(None);
+        };
+        self.key = Some(name);
+        self.next_value = Some(node);
+        seed.deserialize(BorrowedStrDeserializer::new(name))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'a>>(&mut self, seed: V) -> Result<V::Value> {
+        let node = self
+            .next_value
+            .take()
+            .ok_or_else(|| de::Error::custom("value without a key"))?;
+        seed.deserialize(ValueDeserializer {
+            values: node.values,
+            children: Some(&node.children),
+        })
+        .map_err(|error| error.in_field(self.key.unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Sort {
+        Newest,
+        PriceAsc,
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    struct Price {
+        min: Option<u32>,
+        max: Option<u32>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Filter {
+        query: Option<String>,
+        page: u32,
+        in_stock: bool,
+        #[serde(default)]
+        tag: Vec<String>,
+        sort: Sort,
+        #[serde(default)]
+        price: Price,
+    }
+
+    #[test]
+    fn struct_round_trip() {
+        let filter = Filter {
+            query: Some("shoes".to_owned()),
+            page: 2,
+            in_stock: true,
+            tag: vec!["new".to_owned(), "sale".to_owned()],
+            sort: Sort::PriceAsc,
+            price: Price {
+                min: Some(10),
+                max: None,
+            },
+        };
+        let search = UrlSearch::from_struct(&filter).unwrap();
+        assert_eq!(
+            search,
+            UrlSearch::new(vec![
+                ("query", vec!["shoes"]),
+                ("page", vec!["2"]),
+                ("in_stock", vec!["true"]),
+                ("tag", vec!["new", "sale"]),
+                ("sort", vec!["price_asc"]),
+                ("price.min", vec!["10"]),
+            ])
+        );
+        assert_eq!(search.to_struct::<Filter>().unwrap(), filter);
+
+        let url = crate::browser::Url::new()
+            .set_search_struct(&filter)
+            .unwrap();
+        assert_eq!(url.search(), &search);
+    }
+
+    #[test]
+    fn optional_and_default_fields_may_be_missing() {
+        let search = UrlSearch::new(vec![
+            ("query", vec![""]),
+            ("page", vec!["1"]),
+            ("in_stock", vec!["false"]),
+            ("sort", vec!["newest"]),
+            ("unknown", vec!["ignored"]),
+        ]);
+        assert_eq!(
+            search.to_struct::<Filter>().unwrap(),
+            Filter {
+                query: None,
+                page: 1,
+                in_stock: false,
+                tag: Vec::new(),
+                sort: Sort::Newest,
+                price: Price::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_reported_with_keys() {
+        let search = |page: &str, sort: &str, min: &str| {
+            UrlSearch::new(vec![
+                ("page", vec![page]),
+                ("in_stock", vec!["true"]),
+                ("sort", vec![sort]),
+                ("price.min", vec![min]),
+            ])
+        };
+
+        let error = search("abc", "newest", "1")
+            .to_struct::<Filter>()
+            .unwrap_err();
+        assert_eq!(error.key(), Some("page"));
+        assert_eq!(
+            error.to_string(),
+            "search parameter `page`: invalid value `abc`: invalid digit found in string"
+        );
+
+        let error = search("1", "oldest", "1")
+            .to_struct::<Filter>()
+            .unwrap_err();
+        assert_eq!(error.key(), Some("sort"));
+
+        let error = search("1", "newest", "-5")
+            .to_struct::<Filter>()
+            .unwrap_err();
+        assert_eq!(error.key(), Some("price.min"));
+
+        let error = UrlSearch::new(vec![("page", vec!["1", "2"])])
+            .to_struct::<Filter>()
+            .unwrap_err();
+        assert_eq!(error.key(), Some("page"));
+        assert_eq!(error.message(), "expected a single value, found 2");
+
+        let error = UrlSearch::new(vec![("page", vec!["1"])])
+            .to_struct::<Filter>()
+            .unwrap_err();
+        assert_eq!(error.key(), None);
+        assert_eq!(error.message(), "missing field `in_stock`");
+    }
+
+    #[test]
+    fn unsupported_values_are_rejected() {
+        #[derive(Serialize)]
+        struct Nested {
+            pages: Vec<Vec<u32>>,
+        }
+        let error = UrlSearch::from_struct(&Nested {
+            pages: vec![vec![1]],
+        })
+        .unwrap_err();
+        assert_eq!(error.key(), Some("pages"));
+
+        assert!(UrlSearch::from_struct(&5).is_err());
+    }
+}
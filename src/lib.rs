@@ -58,36 +58,6 @@
 )]
 #![allow(deprecated)]
 
-// @TODO Refactor once `optin_builtin_traits` or `negative_impls`
-// @TODO is stable (https://github.com/seed-rs/seed/issues/391).
-// --
-// @TODO Remove `'static` bound from all `MsU`s once `optin_builtin_traits`, `negative_impls`
-// @TODO or https://github.com/rust-lang/rust/issues/41875 is stable.
-macro_rules! map_callback_return_to_option_ms {
-    ($cb_type:ty, $callback:expr, $panic_text:literal, $output_type:tt) => {{
-        let t_type = std::any::TypeId::of::<MsU>();
-        if t_type == std::any::TypeId::of::<Ms>() {
-            $output_type::new(move |value| {
-                (&mut Some($callback(value)) as &mut dyn std::any::Any)
-                    .downcast_mut::<Option<Ms>>()
-                    .and_then(Option::take)
-            })
-        } else if t_type == std::any::TypeId::of::<Option<Ms>>() {
-            $output_type::new(move |value| {
-                (&mut $callback(value) as &mut dyn std::any::Any)
-                    .downcast_mut::<Option<Ms>>()
-                    .and_then(Option::take)
-            })
-        } else if t_type == std::any::TypeId::of::<()>() {
-            $output_type::new(move |value| {
-                $callback(value);
-                None
-            }) as $output_type<$cb_type>
-        } else {
-            panic!($panic_text);
-        }
-    }};
-}
 // @TODO move to prelude (?)
 pub use crate::{
     app::App,
@@ -102,6 +72,12 @@ pub use crate::{
     virtual_dom::{Attrs, EventHandler, Style},
 };
 
+#[cfg(feature = "fetch")]
+pub use browser::fetch;
+pub use browser::router;
+#[cfg(feature = "serde")]
+pub use browser::storage;
+
 pub use futures::{
     self,
     future::{self, FutureExt, TryFutureExt},
@@ -115,6 +91,9 @@ pub mod app;
 pub mod browser;
 pub mod dom_entity_names;
 pub mod helpers;
+pub mod ssr;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod virtual_dom;
 
 /// Create an element flagged in a way that it will not be rendered. Useful
@@ -170,11 +149,14 @@ pub fn set_timeout(handler: Box<dyn Fn()>, timeout: i32) {
 /// Expose the `wasm_bindgen` prelude.
 pub mod prelude {
     #[cfg(feature = "routing")]
-    pub use crate::app::subs;
+    pub use crate::app::{subs, RoutingMode, ScrollRestoration};
+    #[cfg(panic = "unwind")]
+    pub use crate::virtual_dom::error_boundary;
     pub use crate::{
         app::{
-            cmds, streams, App, CmdHandle, GetElement, MessageMapper, Orders, RenderInfo,
-            StreamHandle, SubHandle,
+            cmds, streams, App, CmdHandle, GetElement, IntoOptionMsg, Jitter, MessageMapper,
+            MiddlewareAction, MiddlewareContext, Orders, PanicContext, RenderInfo, Retries,
+            RetryPolicy, StreamHandle, SubHandle,
         },
         browser::dom::css_units::*,
         browser::dom::event_handler::{
@@ -185,7 +167,7 @@ pub mod prelude {
         browser::util::{
             request_animation_frame, RequestAnimationFrameHandle, RequestAnimationFrameTime,
         },
-        browser::{Url, UrlSearch},
+        browser::{router::Routable, Url, UrlSearch},
         helpers::not,
         // macros are exported in crate root
         // https://github.com/rust-lang-nursery/reference/blob/master/src/macros-by-example.md
@@ -250,7 +232,8 @@ pub mod tests {
 
         #[wasm_bindgen]
         pub fn render() {
-            seed::App::start("render test app", |_, _| Model::default(), update, view);
+            seed::App::start("render test app", |_, _| Model::default(), update, view)
+                .window_events(window_events);
         }
     }
 }
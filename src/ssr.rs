@@ -0,0 +1,294 @@
+//! Server-side rendering - render a view to an HTML string without a browser.
+//!
+//! Functions in this module don't call any browser API, so they can be used also on native
+//! (non-wasm) targets - e.g. in a build script or in a web server that prerenders your pages.
+//!
+//! _Note:_ `init` is invoked with a detached `OrdersContainer`. Messages, notifications
+//! and render requests are ignored; cmds, streams, subscriptions and `after_next_render`
+//! callbacks are dropped without being executed. Don't call browser APIs in `init` and `view`
+//! when you want to prerender them on a native target.
+//!
+//! There is no running `App` either - `Orders::clone_app` panics in a detached `OrdersContainer`.
+//! Call `App` methods on the `App` returned by `App::start`, or call `orders.clone_app()` only
+//! when `init` runs in the browser (e.g. behind `#[cfg(target_arch = "wasm32")]` when you prerender
+//! on a native target).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let url = Url::new().set_path(["docs", "install"]);
+//! let html = seed::ssr::render_to_string(url, init, view);
+//! // or
+//! let html = App::render_to_string(url, init, view);
+//! ```
+
+use crate::app::OrdersContainer;
+use crate::browser::dom::Namespace;
+use crate::browser::Url;
+use crate::virtual_dom::{At, AtValue, El, IntoNodes, Node};
+use std::fmt::Write as _;
+
+// https://html.spec.whatwg.org/multipage/syntax.html#void-elements
+// (+ legacy elements from https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments)
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "basefont", "bgsound", "br", "col", "embed", "frame", "hr", "img", "input",
+    "keygen", "link", "meta", "param", "source", "track", "wbr",
+];
+
+// Text content of these elements isn't escaped.
+// https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments
+const RAW_TEXT_ELEMENTS: &[&str] = &[
+    "style",
+    "script",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "plaintext",
+];
+
+/// Invoke `init` and `view` and render the result to an HTML string.
+///
+/// See the [module documentation](index.html) for more info.
+pub fn render_to_string<Ms, Mdl, INodes>(
+    url: Url,
+    init: impl FnOnce(Url, &mut OrdersContainer<Ms, Mdl, INodes>) -> Mdl,
+    view: impl FnOnce(&Mdl) -> INodes,
+) -> String
+where
+    Ms: 'static,
+    Mdl: 'static,
+    INodes: IntoNodes<Ms> + 'static,
+{
+    let mut orders = OrdersContainer::new_detached();
+    let model = init(url, &mut orders);
+    nodes_to_string(&view(&model).into_nodes())
+}
+
+/// Render nodes to an HTML string.
+///
+/// - Text and attribute values are escaped.
+/// - Void elements (e.g. `<br>`) don't have end tags and children.
+/// - Attributes with `AtValue::None` are rendered as boolean attributes (e.g. `<input disabled>`).
+/// - `xmlns` is rendered only on elements where the namespace changes (e.g. on the root `<svg>`).
+pub fn nodes_to_string<Ms>(nodes: &[Node<Ms>]) -> String {
+    let mut html = String::new();
+    for node in nodes {
+        write_node(node, None, false, &mut html);
+    }
+    html
+}
+
+/// See `nodes_to_string`.
+pub fn node_to_string<Ms>(node: &Node<Ms>) -> String {
+    nodes_to_string(std::slice::from_ref(node))
+}
+
+fn write_node<Ms>(
+    node: &Node<Ms>,
+    parent_namespace: Option<&Namespace>,
+    raw_text: bool,
+    html: &mut String,
+) {
+    match node {
+        Node::Element(el) => write_el(el, parent_namespace, html),
+        Node::Text(text) if raw_text => html.push_str(&text.text),
+        Node::Text(text) => escape_text(&text.text, html),
+        Node::Empty | Node::NoChange => (),
+    }
+}
+
+fn write_el<Ms>(el: &El<Ms>, parent_namespace: Option<&Namespace>, html: &mut String) {
+    let namespace = el.namespace.as_ref().filter(|ns| ns != &&Namespace::Html);
+    // HTML tag names are case-insensitive (and e.g. `Tag::Script` is "Script"),
+    // but foreign elements like `linearGradient` have to keep their case.
+    let tag = match namespace {
+        None => el.tag.as_str().to_ascii_lowercase(),
+        Some(_) => el.tag.as_str().to_owned(),
+    };
+    let is_html_tag = |name: &str| namespace.is_none() && tag == name;
+
+    let _ = write!(html, "<{tag}");
+
+    let style = el.style.to_string();
+    for (at, at_value) in &el.attrs.vals {
+        match at {
+            // `Style` has higher priority than `At::Style` - see `make_websys_el`.
+            At::Style if !style.is_empty() => continue,
+            At::Xmlns if namespace.is_some() => continue,
+            // Textarea's value is rendered as its content.
+            At::Value if is_html_tag("textarea") => continue,
+            _ => write_attr(at.as_str(), at_value, html),
+        }
+    }
+    if !style.is_empty() {
+        write_attr(At::Style.as_str(), &AtValue::Some(style), html);
+    }
+    if let Some(namespace) = namespace {
+        if parent_namespace != Some(namespace) {
+            write_attr(
+                At::Xmlns.as_str(),
+                &AtValue::Some(namespace.as_str().to_owned()),
+                html,
+            );
+        }
+    }
+    html.push('>');
+
+    if namespace.is_none() && VOID_ELEMENTS.contains(&tag.as_str()) {
+        return;
+    }
+
+    if let (true, Some(AtValue::Some(value))) =
+        (is_html_tag("textarea"), el.attrs.vals.get(&At::Value))
+    {
+        escape_text(value, html);
+    } else {
+        let raw_text = namespace.is_none() && RAW_TEXT_ELEMENTS.contains(&tag.as_str());
+        for child in &el.children {
+            write_node(child, namespace, raw_text, html);
+        }
+    }
+
+    let _ = write!(html, "</{tag}>");
+}
+
+fn write_attr(name: &str, value: &AtValue, html: &mut String) {
+    match value {
+        AtValue::Ignored => (),
+        AtValue::None => {
+            let _ = write!(html, " {name}");
+        }
+        AtValue::Some(value) => {
+            let _ = write!(html, " {name}=\"");
+            escape_attr_value(value, html);
+            html.push('"');
+        }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#escapingString
+fn escape_text(text: &str, html: &mut String) {
+    for character in text.chars() {
+        match character {
+            '&' => html.push_str("&amp;"),
+            '\u{a0}' => html.push_str("&nbsp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            _ => html.push(character),
+        }
+    }
+}
+
+// https://html.spec.whatwg.org/multipage/parsing.html#escapingString
+fn escape_attr_value(value: &str, html: &mut String) {
+    for character in value.chars() {
+        match character {
+            '&' => html.push_str("&amp;"),
+            '\u{a0}' => html.push_str("&nbsp;"),
+            '"' => html.push_str("&quot;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            _ => html.push(character),
+        }
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+// These tests don't need a browser - run them with `cargo test`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(Clone)]
+    enum Msg {
+        Clicked,
+    }
+
+    #[test]
+    fn escape_text_and_attributes() {
+        let node: Node<Msg> = div![
+            attrs! {At::Title => r#"Tom & "Jerry" <3"#},
+            "1 < 2 && 3 > 2",
+        ];
+        assert_eq!(
+            node_to_string(&node),
+            r#"<div title="Tom &amp; &quot;Jerry&quot; &lt;3">1 &lt; 2 &amp;&amp; 3 &gt; 2</div>"#
+        );
+    }
+
+    #[test]
+    fn void_elements_and_boolean_attributes() {
+        let node: Node<Msg> = div![
+            input![attrs! {
+                At::Disabled => AtValue::None,
+                At::Value => "a",
+                At::Checked => AtValue::Ignored,
+            }],
+            button!["Click", ev(Ev::Click, |_| Msg::Clicked)],
+        ];
+        assert_eq!(
+            node_to_string(&node),
+            r#"<div><input disabled value="a"><button>Click</button></div>"#
+        );
+    }
+
+    #[test]
+    fn classes_and_style() {
+        let node: Node<Msg> = div![C!["a", "b"], style! {St::Display => "flex"}];
+        assert_eq!(
+            node_to_string(&node),
+            r#"<div class="a b" style="display:flex"></div>"#
+        );
+    }
+
+    #[test]
+    fn svg_namespace() {
+        let node: Node<Msg> = div![svg![circle![attrs! {At::R => 5}]]];
+        assert_eq!(
+            node_to_string(&node),
+            r#"<div><svg xmlns="http://www.w3.org/2000/svg"><circle r="5"></circle></svg></div>"#
+        );
+    }
+
+    #[test]
+    fn raw_text_and_textarea() {
+        let nodes: Vec<Node<Msg>> = vec![
+            Script!["if (a < b) {}"],
+            textarea![attrs! {At::Value => "<b>"}],
+        ];
+        assert_eq!(
+            nodes_to_string(&nodes),
+            "<script>if (a < b) {}</script><textarea>&lt;b&gt;</textarea>"
+        );
+    }
+
+    #[test]
+    fn render_app_to_string() {
+        struct Model {
+            path: Vec<String>,
+        }
+
+        fn init(url: Url, orders: &mut impl Orders<Msg>) -> Model {
+            orders
+                .send_msg(Msg::Clicked)
+                .perform_cmd(async { Msg::Clicked })
+                .subscribe(|_: &'static str| Msg::Clicked);
+            Model {
+                path: url.path().to_vec(),
+            }
+        }
+
+        fn view(model: &Model) -> Node<Msg> {
+            div![model.path.join("/")]
+        }
+
+        let url = Url::new().set_path(["docs", "install"]);
+        assert_eq!(
+            App::render_to_string(url, init, view),
+            "<div>docs/install</div>"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use super::{find_all, Selector, TestOrders};
+use crate::app::{Effect, Orders};
+use crate::browser::Url;
+use crate::virtual_dom::{El, Ev, IntoNodes, Node};
+use std::{any::Any, collections::VecDeque};
+use wasm_bindgen::{JsCast, JsValue};
+
+type UpdateFn<Ms, Mdl> = Box<dyn Fn(Ms, &mut Mdl, &mut TestOrders<Ms>)>;
+type ViewFn<Ms, Mdl> = Box<dyn Fn(&Mdl) -> Vec<Node<Ms>>>;
+
+// ------ TestApp ------
+
+/// Headless app for testing `init`, `update` and `view` together without a DOM.
+///
+/// Messages and notifications are processed like in the real `App`, but cmds and streams
+/// are only recorded - run them by `run_cmds` or through `orders_mut`.
+/// The view is rendered after each processed batch of messages.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut app = TestApp::start(Url::new(), init, update, view);
+///
+/// app.trigger(&Selector::text("Increment"), Ev::Click);
+///
+/// assert_eq!(app.model().counter, 1);
+/// assert!(app.find(&Selector::text("Count: 1")).is_some());
+/// ```
+pub struct TestApp<Ms: 'static, Mdl> {
+    model: Mdl,
+    orders: TestOrders<Ms>,
+    update: UpdateFn<Ms, Mdl>,
+    view: ViewFn<Ms, Mdl>,
+    nodes: Vec<Node<Ms>>,
+}
+
+impl<Ms: 'static, Mdl: 'static> TestApp<Ms, Mdl> {
+    /// Invoke `init`, process messages and notifications it has sent and render the view.
+    pub fn start<INodes: IntoNodes<Ms>>(
+        url: Url,
+        init: impl FnOnce(Url, &mut TestOrders<Ms>) -> Mdl,
+        update: impl FnOnce(Ms, &mut Mdl, &mut TestOrders<Ms>) + Clone + 'static,
+        view: impl FnOnce(&Mdl) -> INodes + Clone + 'static,
+    ) -> Self {
+        let mut orders = TestOrders::new();
+        let model = init(url, &mut orders);
+        let effects = orders.take_effects();
+
+        let mut app = Self {
+            model,
+            orders,
+            update: Box::new(move |msg, model, orders| update.clone()(msg, model, orders)),
+            view: Box::new(move |model| view.clone()(model).into_nodes()),
+            nodes: Vec::new(),
+        };
+        app.process_effects(effects);
+        app
+    }
+
+    pub fn model(&self) -> &Mdl {
+        &self.model
+    }
+
+    /// `Orders` passed to `init` and `update` - use it to check and run recorded effects.
+    pub fn orders(&self) -> &TestOrders<Ms> {
+        &self.orders
+    }
+
+    pub fn orders_mut(&mut self) -> &mut TestOrders<Ms> {
+        &mut self.orders
+    }
+
+    /// The last view output.
+    pub fn nodes(&self) -> &[Node<Ms>] {
+        &self.nodes
+    }
+
+    /// Find all elements matching the `selector`.
+    pub fn find_all(&self, selector: &Selector) -> Vec<&El<Ms>> {
+        find_all(&self.nodes, selector)
+    }
+
+    /// Find the first element matching the `selector`.
+    pub fn find(&self, selector: &Selector) -> Option<&El<Ms>> {
+        self.find_all(selector).into_iter().next()
+    }
+
+    /// Process the message and rerender.
+    pub fn update(&mut self, msg: Ms) {
+        self.process_effects(VecDeque::from([Effect::Msg(Some(msg))]));
+    }
+
+    /// Notify subscribers and rerender.
+    pub fn notify(&mut self, message: impl Any + Clone) {
+        self.orders.notify(message);
+        let effects = self.orders.take_effects();
+        self.process_effects(effects);
+    }
+
+    /// Run recorded cmds, process their messages and rerender.
+    ///
+    /// See `TestOrders::run_cmds` for limitations.
+    pub fn run_cmds(&mut self) {
+        let effects = self
+            .orders
+            .run_cmds()
+            .into_iter()
+            .map(|msg| Effect::Msg(Some(msg)))
+            .collect();
+        self.process_effects(effects);
+    }
+
+    /// Invoke event handlers with the `trigger` attached to the first element matching the `selector`
+    /// with a synthetic event, process their messages and rerender.
+    ///
+    /// The synthetic event doesn't contain any data, so it works only with handlers that ignore
+    /// the event - e.g. `ev(Ev::Click, |_| Msg::Clicked)`. Use `trigger_with_event` in browser tests
+    /// for other handlers (e.g. `input_ev` or `keyboard_ev`).
+    ///
+    /// # Panics
+    ///
+    /// Panics when no element matches the `selector`.
+    pub fn trigger(&mut self, selector: &Selector, trigger: impl Into<Ev>) {
+        let synthetic_event = || JsValue::UNDEFINED.unchecked_into::<web_sys::Event>();
+        self.trigger_with(selector, trigger.into(), synthetic_event);
+    }
+
+    /// Invoke event handlers with the `trigger` attached to the first element matching the `selector`
+    /// with the given `event`, process their messages and rerender.
+    ///
+    /// # Panics
+    ///
+    /// Panics when no element matches the `selector`.
+    pub fn trigger_with_event(
+        &mut self,
+        selector: &Selector,
+        trigger: impl Into<Ev>,
+        event: &web_sys::Event,
+    ) {
+        self.trigger_with(selector, trigger.into(), || event.clone());
+    }
+
+    fn trigger_with(
+        &mut self,
+        selector: &Selector,
+        trigger: Ev,
+        event: impl Fn() -> web_sys::Event,
+    ) {
+        let handlers = self
+            .find(selector)
+            .unwrap_or_else(|| panic!("no element matches {selector:?}"))
+            .event_handler_manager
+            .event_handlers(&trigger);
+
+        let effects = handlers
+            .into_iter()
+            .map(|handler| Effect::Msg((handler.callback)(event())))
+            .collect();
+        self.process_effects(effects);
+    }
+
+    fn process_effects(&mut self, mut queue: VecDeque<Effect<Ms>>) {
+        while let Some(effect) = queue.pop_front() {
+            let msg = match effect {
+                Effect::Msg(msg) => msg,
+                Effect::Notification(notification) => {
+                    queue.extend(
+                        self.orders
+                            .subscription_handlers(&notification)
+                            .into_iter()
+                            .map(Effect::TriggeredHandler),
+                    );
+                    None
+                }
+                Effect::TriggeredHandler(handler) => handler(),
+            };
+            if let Some(msg) = msg {
+                (self.update)(msg, &mut self.model, &mut self.orders);
+                queue.append(&mut self.orders.take_effects());
+            }
+        }
+        self.nodes = (self.view)(&self.model);
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+// These tests don't need a browser - run them with `cargo test`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Orders;
+    use crate::prelude::*;
+
+    #[derive(Clone)]
+    enum Msg {
+        Increment,
+        Reset,
+        Loaded(u32),
+    }
+
+    struct Model {
+        counter: u32,
+    }
+
+    fn init(_: Url, orders: &mut impl Orders<Msg>) -> Model {
+        orders
+            .subscribe(|_: &'static str| Msg::Reset)
+            .perform_cmd(async { Msg::Loaded(5) });
+        Model { counter: 0 }
+    }
+
+    fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg>) {
+        match msg {
+            Msg::Increment => {
+                model.counter += 1;
+                if model.counter == 2 {
+                    orders.send_msg(Msg::Increment);
+                }
+            }
+            Msg::Reset => model.counter = 0,
+            Msg::Loaded(counter) => model.counter = counter,
+        }
+    }
+
+    fn view(model: &Model) -> Node<Msg> {
+        div![
+            C!["counter"],
+            ul![(0..model.counter).map(|i| li![el_key(&i), format!("Item {i}")])],
+            button![
+                id!["increment"],
+                "Increment",
+                ev(Ev::Click, |_| Msg::Increment)
+            ],
+        ]
+    }
+
+    #[test]
+    fn trigger_events_and_query_view() {
+        let mut app = TestApp::start(Url::new(), init, update, view);
+        assert_eq!(app.find_all(&Selector::Tag(Tag::Li)).len(), 0);
+
+        app.trigger(&Selector::text("Increment"), Ev::Click);
+        assert_eq!(app.model().counter, 1);
+
+        app.trigger(&Selector::id("increment"), Ev::Click);
+        // The second click sends one more `Increment`.
+        assert_eq!(app.model().counter, 3);
+        assert_eq!(app.find_all(&Selector::Tag(Tag::Li)).len(), 3);
+        assert_eq!(
+            app.find(&Selector::key(&2)).map(El::get_text).as_deref(),
+            Some("Item 2")
+        );
+        assert!(app.find(&Selector::class("counter")).is_some());
+
+        app.notify("reset");
+        assert_eq!(app.model().counter, 0);
+
+        app.run_cmds();
+        assert_eq!(app.model().counter, 5);
+    }
+}
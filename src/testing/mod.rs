@@ -0,0 +1,16 @@
+//! Helpers for testing Seed apps without a browser.
+//!
+//! Everything in this module runs also on native (non-wasm) targets - i.e. with plain `cargo test`.
+//! It's available with the feature `testing` - enable it only for tests, e.g. in `[dev-dependencies]`.
+//!
+//! - `TestOrders` - test `update` functions and check recorded effects.
+//! - `TestApp` - run `init`, `update` and `view` together, query the view output
+//!   by `Selector`s and simulate events.
+
+mod app;
+mod orders;
+mod selector;
+
+pub use app::TestApp;
+pub use orders::TestOrders;
+pub use selector::{find_all, Selector};
@@ -0,0 +1,395 @@
+use crate::app::orders::container::{Detached, Runtime};
+use crate::app::{
+    App, CmdHandle, Effect, IntoOptionMsg, Notification, Orders, OrdersContainer, OrdersProxy,
+    RenderInfo, ShouldRender, StreamHandle, SubHandle,
+};
+use crate::virtual_dom::Node;
+use futures::executor::block_on;
+use futures::stream::{Stream, StreamExt};
+use std::{any::Any, collections::VecDeque, future::Future, rc::Rc};
+
+#[cfg(feature = "routing")]
+use crate::{app::subs, browser::Url};
+
+// ------ TestOrders ------
+
+/// `Orders` implementation that only records what your code asked for.
+///
+/// Use it to test your `update` (and `init`) functions without a browser.
+/// Recorded cmds, streams and subscriptions are executed only on demand.
+///
+/// _Note:_ `clone_app` panics because there is no `App`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut model = Model::default();
+/// let mut orders = TestOrders::new();
+///
+/// update(Msg::Fetch, &mut model, &mut orders);
+///
+/// assert!(model.loading);
+/// assert_eq!(orders.should_render(), ShouldRender::Skip);
+/// assert_eq!(orders.run_cmds(), vec![Msg::Fetched(Ok(42))]);
+/// ```
+pub struct TestOrders<Ms: 'static> {
+    container: OrdersContainer<Ms, (), Node<Ms>>,
+}
+
+impl<Ms: 'static> Default for TestOrders<Ms> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ms: 'static> TestOrders<Ms> {
+    pub fn new() -> Self {
+        Self {
+            container: OrdersContainer::new_detached(),
+        }
+    }
+
+    /// Messages sent by `send_msg` in the order they were sent.
+    pub fn sent_msgs(&self) -> Vec<&Ms> {
+        self.container
+            .effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Msg(Some(msg)) => Some(msg),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Messages of type `SubMs` sent by `notify`.
+    pub fn notifications<SubMs: 'static>(&self) -> Vec<&SubMs> {
+        self.container
+            .effects
+            .iter()
+            .filter_map(|effect| match effect {
+                Effect::Notification(notification) => notification.downcast_ref(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Urls requested by `request_url` (or by `notify(subs::UrlRequested::new(url))`).
+    #[cfg(feature = "routing")]
+    pub fn requested_urls(&self) -> Vec<&Url> {
+        self.notifications::<subs::UrlRequested>()
+            .into_iter()
+            .map(|subs::UrlRequested(url, _)| url)
+            .collect()
+    }
+
+    /// The last render request - i.e. `Render` (the default), `ForceRenderNow` or `Skip`.
+    pub fn should_render(&self) -> ShouldRender {
+        self.container.should_render
+    }
+
+    /// The number of recorded cmds that haven't been run yet.
+    pub fn cmd_count(&self) -> usize {
+        self.detached().cmds.len()
+    }
+
+    /// Run all recorded cmds to completion and return their messages.
+    ///
+    /// _Note:_ Cmds are run by a simple executor on the current thread,
+    /// so they must not depend on browser APIs (e.g. timers or `fetch`).
+    pub fn run_cmds(&mut self) -> Vec<Ms> {
+        let cmds = std::mem::take(&mut self.detached_mut().cmds);
+        cmds.into_iter().filter_map(block_on).collect()
+    }
+
+    /// The number of recorded streams that haven't been run yet.
+    pub fn stream_count(&self) -> usize {
+        self.detached().streams.len()
+    }
+
+    /// Run all recorded streams until they end and return their messages.
+    ///
+    /// _Note:_ It never returns for infinite streams. See also `run_cmds`.
+    pub fn run_streams(&mut self) -> Vec<Ms> {
+        let streams = std::mem::take(&mut self.detached_mut().streams);
+        streams
+            .into_iter()
+            .flat_map(|stream| block_on(stream.collect::<Vec<_>>()))
+            .flatten()
+            .collect()
+    }
+
+    /// Invoke recorded subscription handlers listening for `SubMs` and return their messages.
+    pub fn notify_subscribers<SubMs: 'static + Any + Clone>(&self, message: SubMs) -> Vec<Ms> {
+        self.detached()
+            .sub_manager
+            .notify(&Notification::new(message))
+            .into_iter()
+            .filter_map(|handler| handler())
+            .collect()
+    }
+
+    /// Invoke recorded `after_next_render` callbacks and return their messages.
+    pub fn run_after_next_render(&mut self, render_info: RenderInfo) -> Vec<Ms> {
+        let callbacks = std::mem::take(&mut self.detached_mut().after_next_render_callbacks);
+        callbacks
+            .into_iter()
+            .filter_map(|callback| callback(render_info))
+            .collect()
+    }
+
+    /// Take messages and notifications recorded since the last call.
+    pub(crate) fn take_effects(&mut self) -> VecDeque<Effect<Ms>> {
+        std::mem::take(&mut self.container.effects)
+    }
+
+    /// Recorded subscription handlers listening for the notification.
+    pub(crate) fn subscription_handlers(
+        &self,
+        notification: &Notification,
+    ) -> Vec<Box<dyn FnOnce() -> Option<Ms>>> {
+        self.detached().sub_manager.notify(notification)
+    }
+
+    fn detached(&self) -> &Detached<Ms> {
+        match &self.container.runtime {
+            Runtime::Detached(detached) => detached,
+            Runtime::App(_) => unreachable!("`TestOrders` container is always detached"),
+        }
+    }
+
+    fn detached_mut(&mut self) -> &mut Detached<Ms> {
+        match &mut self.container.runtime {
+            Runtime::Detached(detached) => detached,
+            Runtime::App(_) => unreachable!("`TestOrders` container is always detached"),
+        }
+    }
+}
+
+impl<Ms: 'static> Orders<Ms> for TestOrders<Ms> {
+    type AppMs = Ms;
+    type Mdl = ();
+    type INodes = Node<Ms>;
+
+    fn proxy<ChildMs: 'static>(
+        &mut self,
+        f: impl FnOnce(ChildMs) -> Ms + 'static + Clone,
+    ) -> OrdersProxy<'_, ChildMs, Ms, (), Node<Ms>> {
+        self.container.proxy(f)
+    }
+
+    fn render(&mut self) -> &mut Self {
+        self.container.render();
+        self
+    }
+
+    fn force_render_now(&mut self) -> &mut Self {
+        self.container.force_render_now();
+        self
+    }
+
+    fn skip(&mut self) -> &mut Self {
+        self.container.skip();
+        self
+    }
+
+    fn notify(&mut self, message: impl Any + Clone) -> &mut Self {
+        self.container.notify(message);
+        self
+    }
+
+    fn send_msg(&mut self, msg: Ms) -> &mut Self {
+        self.container.send_msg(msg);
+        self
+    }
+
+    fn perform_cmd<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self {
+        self.container.perform_cmd(cmd);
+        self
+    }
+
+    fn perform_cmd_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> CmdHandle {
+        self.container.perform_cmd_with_handle(cmd)
+    }
+
+    fn perform_cmd_keyed<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        key: impl Into<String>,
+        cmd: impl Future<Output = MsU> + 'static,
+    ) -> &mut Self {
+        self.container.perform_cmd_keyed(key, cmd);
+        self
+    }
+
+    fn cancel_cmd(&mut self, key: &str) -> &mut Self {
+        self.container.cancel_cmd(key);
+        self
+    }
+
+    fn is_cmd_pending(&self, key: &str) -> bool {
+        self.container.is_cmd_pending(key)
+    }
+
+    fn clone_app(&self) -> App<Ms, (), Node<Ms>> {
+        panic!("`App` isn't available in `TestOrders`")
+    }
+
+    fn msg_mapper(&self) -> Rc<dyn Fn(Ms) -> Ms> {
+        self.container.msg_mapper()
+    }
+
+    fn msg_sender(&self) -> Rc<dyn Fn(Option<Ms>)> {
+        self.container.msg_sender()
+    }
+
+    fn after_next_render<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        callback: impl FnOnce(RenderInfo) -> MsU + 'static,
+    ) -> &mut Self {
+        self.container.after_next_render(callback);
+        self
+    }
+
+    fn subscribe<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker, SubMs: 'static + Clone>(
+        &mut self,
+        handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
+    ) -> &mut Self {
+        self.container.subscribe(handler);
+        self
+    }
+
+    fn subscribe_with_handle<
+        MsU: IntoOptionMsg<Ms, Marker> + 'static,
+        Marker,
+        SubMs: 'static + Clone,
+    >(
+        &mut self,
+        handler: impl FnOnce(SubMs) -> MsU + Clone + 'static,
+    ) -> SubHandle {
+        self.container.subscribe_with_handle(handler)
+    }
+
+    fn stream<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        stream: impl Stream<Item = MsU> + 'static,
+    ) -> &mut Self {
+        self.container.stream(stream);
+        self
+    }
+
+    fn stream_with_handle<MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
+        &mut self,
+        stream: impl Stream<Item = MsU> + 'static,
+    ) -> StreamHandle {
+        self.container.stream_with_handle(stream)
+    }
+
+    fn clone_base_path(&self) -> Rc<[String]> {
+        self.container.clone_base_path()
+    }
+}
+
+// ------ ------ Tests ------ ------
+
+// These tests don't need a browser - run them with `cargo test`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::ready;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Msg {
+        Increment,
+        Fetched(u32),
+        Child(ChildMsg),
+        Tick,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum ChildMsg {
+        Loaded,
+    }
+
+    fn update(msg: Msg, model: &mut u32, orders: &mut impl Orders<Msg>) {
+        match msg {
+            Msg::Increment => {
+                *model += 1;
+                orders
+                    .skip()
+                    .send_msg(Msg::Tick)
+                    .notify("incremented")
+                    .perform_cmd(async { Msg::Fetched(42) })
+                    .stream(futures::stream::iter([Msg::Tick, Msg::Tick]))
+                    .subscribe(|_: &'static str| Msg::Tick);
+                orders
+                    .proxy(Msg::Child)
+                    .perform_cmd(ready(ChildMsg::Loaded));
+            }
+            Msg::Fetched(_) | Msg::Child(_) | Msg::Tick => (),
+        }
+    }
+
+    #[test]
+    fn record_effects() {
+        let mut model = 0;
+        let mut orders = TestOrders::new();
+
+        update(Msg::Increment, &mut model, &mut orders);
+
+        assert_eq!(model, 1);
+        assert_eq!(orders.should_render(), ShouldRender::Skip);
+        assert_eq!(orders.sent_msgs(), vec![&Msg::Tick]);
+        assert_eq!(orders.notifications::<&'static str>(), vec![&"incremented"]);
+        assert_eq!(orders.cmd_count(), 2);
+        assert_eq!(
+            orders.run_cmds(),
+            vec![Msg::Fetched(42), Msg::Child(ChildMsg::Loaded)]
+        );
+        assert_eq!(orders.cmd_count(), 0);
+        assert_eq!(orders.run_streams(), vec![Msg::Tick, Msg::Tick]);
+        assert_eq!(orders.notify_subscribers("hello"), vec![Msg::Tick]);
+        assert!(orders.notify_subscribers(1_u8).is_empty());
+    }
+
+    #[test]
+    fn aborted_cmd_is_not_run() {
+        let mut orders = TestOrders::<Msg>::new();
+
+        let handle = orders.perform_cmd_with_handle(async { Msg::Tick });
+        drop(handle);
+
+        assert_eq!(orders.cmd_count(), 1);
+        assert!(orders.run_cmds().is_empty());
+    }
+
+    #[test]
+    fn keyed_cmd_replaces_pending_one() {
+        let mut orders = TestOrders::<Msg>::new();
+
+        orders
+            .perform_cmd_keyed("search", async { Msg::Fetched(1) })
+            .perform_cmd_keyed("search", async { Msg::Fetched(2) })
+            .perform_cmd_keyed("other", async { Msg::Fetched(3) })
+            .cancel_cmd("other");
+
+        assert!(orders.is_cmd_pending("search"));
+        assert!(!orders.is_cmd_pending("other"));
+        assert_eq!(orders.run_cmds(), vec![Msg::Fetched(2)]);
+        assert!(!orders.is_cmd_pending("search"));
+    }
+
+    #[cfg(feature = "routing")]
+    #[test]
+    fn guard_navigation_without_app() {
+        let mut orders = TestOrders::<Msg>::new();
+
+        orders.guard_navigation(|_| Msg::Tick);
+
+        assert!(orders.notify_subscribers(1_u8).is_empty());
+    }
+}
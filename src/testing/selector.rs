@@ -0,0 +1,79 @@
+use crate::virtual_dom::{el_key, At, AtValue, El, ElKey, Node, Tag};
+
+// ------ Selector ------
+
+/// Describes elements to find in the view output.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// app.find(&Selector::Tag(Tag::Button));
+/// app.find(&Selector::class("counter"));
+/// app.find(&Selector::key(&item.id));
+/// ```
+#[derive(Debug, Clone)]
+pub enum Selector {
+    Tag(Tag),
+    /// Elements with the class in their `class` attribute.
+    Class(String),
+    Id(String),
+    Key(ElKey),
+    /// Elements whose text children (see `El::get_text`) contain the text.
+    Text(String),
+}
+
+impl Selector {
+    pub fn class(class: impl Into<String>) -> Self {
+        Self::Class(class.into())
+    }
+
+    pub fn id(id: impl Into<String>) -> Self {
+        Self::Id(id.into())
+    }
+
+    pub fn key(key: &impl ToString) -> Self {
+        Self::Key(el_key(key))
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    pub fn matches<Ms>(&self, el: &El<Ms>) -> bool {
+        let attr = |at: &At| match el.attrs.vals.get(at) {
+            Some(AtValue::Some(value)) => Some(value.as_str()),
+            _ => None,
+        };
+        match self {
+            Self::Tag(tag) => &el.tag == tag,
+            Self::Class(class) => attr(&At::Class).map_or(false, |classes| {
+                classes.split_whitespace().any(|c| c == class)
+            }),
+            Self::Id(id) => attr(&At::Id) == Some(id.as_str()),
+            Self::Key(key) => el.key.as_ref() == Some(key),
+            Self::Text(text) => el.get_text().contains(text.as_str()),
+        }
+    }
+}
+
+/// Find all elements matching the `selector` in the depth-first order.
+pub fn find_all<'a, Ms>(nodes: &'a [Node<Ms>], selector: &Selector) -> Vec<&'a El<Ms>> {
+    let mut found = Vec::new();
+    collect_matching(nodes, selector, &mut found);
+    found
+}
+
+fn collect_matching<'a, Ms>(
+    nodes: &'a [Node<Ms>],
+    selector: &Selector,
+    found: &mut Vec<&'a El<Ms>>,
+) {
+    for node in nodes {
+        if let Node::Element(el) = node {
+            if selector.matches(el) {
+                found.push(el);
+            }
+            collect_matching(&el.children, selector, found);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::{IntoNodes, Node};
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+thread_local! {
+    // Number of nested `error_boundary` calls that are building their subtree.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns `true` while a subtree wrapped in `error_boundary` is being built -
+/// i.e. a panic will be caught and it shouldn't be reported as an app failure.
+pub(crate) fn in_error_boundary() -> bool {
+    DEPTH.with(|depth| depth.get() > 0)
+}
+
+/// Build the subtree by `view`. If it panics, render `fallback` with the panic message instead.
+///
+/// _Note:_ It's available only on targets that unwind on panic - e.g. in native tests
+/// and prerendering. `wasm32-unknown-unknown` aborts on panic, so panics can't be caught
+/// in the browser - use `App::on_panic` to replace the stopped app with an error screen there.
+///
+/// # Example
+///
+/// ```rust,ignore
+///div![
+///    error_boundary(
+///        || view_chart(&model.chart),
+///        |message| div![C!["error"], "The chart cannot be displayed: ", message],
+///    ),
+///]
+/// ```
+pub fn error_boundary<Ms, VN, FN>(
+    view: impl FnOnce() -> VN,
+    fallback: impl FnOnce(String) -> FN,
+) -> Vec<Node<Ms>>
+where
+    VN: IntoNodes<Ms>,
+    FN: IntoNodes<Ms>,
+{
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| view().into_nodes()));
+    DEPTH.with(|depth| depth.set(depth.get() - 1));
+
+    result.unwrap_or_else(|payload| fallback(panic_message(payload.as_ref())).into_nodes())
+}
+
+/// Get the message passed to `panic!` (or `expect`, etc.).
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::ssr::nodes_to_string;
+
+    #[test]
+    fn error_boundary_renders_view_or_fallback() {
+        let nodes: Vec<Node<()>> = error_boundary(|| div!["content"], |_| div!["fallback"]);
+        assert_eq!(nodes_to_string(&nodes), "<div>content</div>");
+
+        let nodes: Vec<Node<()>> = error_boundary(
+            || -> Node<()> { panic!("broken view") },
+            |message| div![message],
+        );
+        assert_eq!(nodes_to_string(&nodes), "<div>broken view</div>");
+        assert!(!in_error_boundary());
+    }
+}
@@ -1,3 +1,4 @@
+use super::RateLimit;
 use crate::app::MessageMapper;
 use crate::virtual_dom::Ev;
 use std::{fmt, rc::Rc};
@@ -6,6 +7,7 @@ use std::{fmt, rc::Rc};
 pub struct EventHandler<Ms> {
     pub trigger: Ev,
     pub callback: Rc<dyn Fn(web_sys::Event) -> Option<Ms>>,
+    pub(crate) rate_limit: Option<RateLimit>,
 }
 
 // @TODO remove custom impl once https://github.com/rust-lang/rust/issues/26925 is fixed
@@ -14,6 +16,7 @@ impl<Ms> Clone for EventHandler<Ms> {
         Self {
             trigger: self.trigger.clone(),
             callback: Rc::clone(&self.callback),
+            rate_limit: self.rate_limit,
         }
     }
 }
@@ -26,8 +29,46 @@ impl<Ms> EventHandler<Ms> {
         Self {
             trigger: trigger.into(),
             callback: Rc::new(callback),
+            rate_limit: None,
         }
     }
+
+    /// Send the message produced by the latest event once no events have been fired
+    /// for `ms` milliseconds - e.g. to react to the user's input when they stop typing.
+    ///
+    /// The callback is still invoked for each event, so you can e.g. call `prevent_default` in it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    ///input![input_ev(Ev::Input, Msg::SearchQueryChanged).debounce(300)]
+    /// ```
+    #[must_use]
+    pub fn debounce(mut self, ms: u32) -> Self {
+        self.rate_limit = Some(RateLimit::Debounce(ms));
+        self
+    }
+
+    /// Send at most one message per `ms` milliseconds - the first one immediately and
+    /// the one produced by the latest event fired during the time window when it ends.
+    ///
+    /// The callback is still invoked for each event, so you can e.g. call `prevent_default` in it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    ///div![mouse_ev(Ev::MouseMove, |event| Msg::MouseMoved(event.client_x())).throttle(50)]
+    /// ```
+    #[must_use]
+    pub fn throttle(mut self, ms: u32) -> Self {
+        self.rate_limit = Some(RateLimit::Throttle(ms));
+        self
+    }
+
+    /// The rate limit set by `debounce` or `throttle`.
+    pub const fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit
+    }
 }
 
 impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for EventHandler<Ms> {
@@ -44,6 +85,7 @@ impl<Ms: 'static, OtherMs: 'static> MessageMapper<Ms, OtherMs> for EventHandler<
         EventHandler {
             trigger: self.trigger,
             callback: Rc::new(new_callback),
+            rate_limit: self.rate_limit,
         }
     }
 }
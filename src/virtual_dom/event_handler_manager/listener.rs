@@ -1,7 +1,9 @@
+use super::{RateLimit, RateLimiter};
 use crate::virtual_dom::{Ev, EventHandler, Mailbox};
 use enclose::enc;
 use std::{
     cell::{Cell, RefCell},
+    collections::HashMap,
     fmt,
     rc::Rc,
 };
@@ -19,6 +21,10 @@ pub struct Listener<Ms> {
     callback: Closure<dyn FnMut(web_sys::Event)>,
     // Element where the listener is attached.
     event_target: web_sys::EventTarget,
+    // Rate limits of the current event handlers - rate limiters are reset when they change.
+    rate_limits: RefCell<Vec<Option<RateLimit>>>,
+    // Drops rate limiters together with their pending messages.
+    reset_rate_limiters: Box<dyn Fn()>,
 }
 
 impl<Ms> Listener<Ms> {
@@ -29,23 +35,49 @@ impl<Ms> Listener<Ms> {
         event_handlers: Rc<RefCell<Vec<EventHandler<Ms>>>>,
         mailbox: Mailbox<Ms>,
     ) -> Self {
+        let rate_limits = RefCell::new(rate_limits(&event_handlers.borrow()));
         let portal_to_event_handlers = Portal::new(event_handlers);
+        // Rate limiters of debounced and throttled event handlers, indexed by handler position.
+        // They live in the listener because event handlers are recreated on each render.
+        let rate_limiters = Rc::new(RefCell::new(HashMap::<usize, RateLimiter<Ms>>::new()));
+        let reset_rate_limiters = Box::new(enc!((rate_limiters) move || {
+            rate_limiters.borrow_mut().clear();
+        }));
 
         let callback = Closure::new(
-            enc!((portal_to_event_handlers) move |event: web_sys::Event| {
+            enc!((portal_to_event_handlers, rate_limiters) move |event: web_sys::Event| {
                 let mut handler_callbacks = Vec::new();
                 portal_to_event_handlers.update(|event_handlers| {
                     // We need to clone handler callbacks and call them later
                     // because otherwise the app may crash while mutable borrowing event handlers.
                     // As a trade-off, all callbacks are called although their parents may not exist anymore.
                     for event_handler in event_handlers.borrow().iter() {
-                        handler_callbacks.push(Rc::clone(&event_handler.callback));
+                        handler_callbacks.push((
+                            Rc::clone(&event_handler.callback),
+                            event_handler.rate_limit,
+                        ));
                     }
                     event_handlers
                 });
-                for handler_callback in handler_callbacks {
+                let handler_callbacks = handler_callbacks.into_iter().enumerate();
+                for (index, (handler_callback, rate_limit)) in handler_callbacks {
                     let msg = handler_callback(event.clone());
-                    mailbox.send(msg);
+                    let (rate_limit, msg) = match (rate_limit, msg) {
+                        (Some(rate_limit), Some(msg)) => (rate_limit, msg),
+                        (_, msg) => {
+                            mailbox.send(msg);
+                            continue;
+                        }
+                    };
+                    let msg = rate_limiters
+                        .borrow_mut()
+                        .entry(index)
+                        .or_insert_with(|| RateLimiter::new(rate_limit))
+                        .limit(msg, &mailbox);
+                    // The limiter isn't borrowed anymore - `send` may rerender the app.
+                    if msg.is_some() {
+                        mailbox.send(msg);
+                    }
                 }
             }),
         );
@@ -59,14 +91,33 @@ impl<Ms> Listener<Ms> {
             callback,
             event_target,
             portal: portal_to_event_handlers,
+            rate_limits,
+            reset_rate_limiters,
         }
     }
 
+    /// Replace event handlers - e.g. with the ones from the new render.
+    ///
+    /// Rate limiters are indexed by handler positions, so they are reset (pending messages are dropped)
+    /// when the rate limits of handlers change - i.e. when a rate-limited handler is added,
+    /// removed or moved.
     pub fn set_event_handlers(&self, event_handlers: Rc<RefCell<Vec<EventHandler<Ms>>>>) {
+        let new_rate_limits = rate_limits(&event_handlers.borrow());
+        if *self.rate_limits.borrow() != new_rate_limits {
+            (self.reset_rate_limiters)();
+            self.rate_limits.replace(new_rate_limits);
+        }
         self.portal.update(|_| event_handlers);
     }
 }
 
+fn rate_limits<Ms>(event_handlers: &[EventHandler<Ms>]) -> Vec<Option<RateLimit>> {
+    event_handlers
+        .iter()
+        .map(|event_handler| event_handler.rate_limit)
+        .collect()
+}
+
 impl<Ms> Drop for Listener<Ms> {
     fn drop(&mut self) {
         self.event_target
@@ -100,3 +151,52 @@ impl<T> Portal<T> {
         self.0.set(self.0.take().map(f));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::util;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn handlers(event_handlers: Vec<EventHandler<u32>>) -> Rc<RefCell<Vec<EventHandler<u32>>>> {
+        Rc::new(RefCell::new(event_handlers))
+    }
+
+    #[wasm_bindgen_test]
+    fn rate_limiters_are_reset_when_handlers_change() {
+        let target: web_sys::EventTarget = util::document().create_element("div").unwrap().into();
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let mailbox = Mailbox::new(enc!((sent) move |msg| sent.borrow_mut().extend(msg)));
+        let throttled = || EventHandler::new(Ev::Click, |_| Some(1)).throttle(10_000);
+        let dispatch = || {
+            target
+                .dispatch_event(&web_sys::Event::new("click").unwrap())
+                .unwrap();
+        };
+
+        let listener = Listener::new(
+            Ev::Click,
+            target.clone(),
+            handlers(vec![throttled()]),
+            mailbox,
+        );
+        dispatch();
+        dispatch();
+        assert_eq!(*sent.borrow(), [1]);
+
+        // The same handlers from the next render keep the throttle window.
+        listener.set_event_handlers(handlers(vec![throttled()]));
+        dispatch();
+        assert_eq!(*sent.borrow(), [1]);
+
+        // The throttled handler has moved - it gets a new limiter.
+        listener.set_event_handlers(handlers(vec![
+            EventHandler::new(Ev::Click, |_| Some(2)),
+            throttled(),
+        ]));
+        dispatch();
+        assert_eq!(*sent.borrow(), [1, 2, 1]);
+    }
+}
@@ -4,9 +4,12 @@ use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 
 pub mod event_handler;
 pub mod listener;
+mod rate_limiter;
 
 pub use event_handler::EventHandler;
 pub use listener::Listener;
+pub use rate_limiter::RateLimit;
+pub(crate) use rate_limiter::RateLimiter;
 
 // ------ EventHandlerManager ------
 
@@ -91,6 +94,14 @@ impl<Ms> EventHandlerManager<Ms> {
         }
     }
 
+    /// Returns event handlers with the given trigger.
+    pub fn event_handlers(&self, trigger: &Ev) -> Vec<EventHandler<Ms>> {
+        self.groups
+            .get(trigger)
+            .map(|group| group.event_handlers.borrow().clone())
+            .unwrap_or_default()
+    }
+
     /// This method is used in `attach_listeners` method to move listeners from the old manager.
     pub fn take_and_setup_listener(
         &mut self,
@@ -0,0 +1,88 @@
+use crate::app::{cmd_manager::CmdManager, CmdHandle};
+use crate::virtual_dom::Mailbox;
+use gloo_timers::future::TimeoutFuture;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+// ------ RateLimit ------
+
+/// How often a rate-limited `EventHandler` sends its messages.
+///
+/// See `EventHandler::debounce` and `EventHandler::throttle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimit {
+    /// Send the latest message once no events have been fired for the given number of milliseconds.
+    Debounce(u32),
+    /// Send at most one message per the given number of milliseconds -
+    /// the first one immediately, the latest one received during the time window when it ends.
+    Throttle(u32),
+}
+
+// ------ RateLimiter ------
+
+/// Delays or drops messages of a rate-limited `EventHandler`.
+///
+/// It's owned by the `Listener`, so its state survives rerenders.
+/// The pending message is dropped together with the `RateLimiter`.
+pub(crate) struct RateLimiter<Ms: 'static> {
+    rate_limit: RateLimit,
+    pending_msg: Rc<RefCell<Option<Ms>>>,
+    // `true` while the throttle time window is open.
+    throttled: Rc<Cell<bool>>,
+    timer: Option<CmdHandle>,
+}
+
+impl<Ms: 'static> RateLimiter<Ms> {
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            rate_limit,
+            pending_msg: Rc::new(RefCell::new(None)),
+            throttled: Rc::new(Cell::new(false)),
+            timer: None,
+        }
+    }
+
+    /// Delay or drop the `msg`. Returns the message that should be sent immediately -
+    /// the caller sends it to avoid reentrant borrows of the limiter.
+    pub fn limit(&mut self, msg: Ms, mailbox: &Mailbox<Ms>) -> Option<Ms> {
+        match self.rate_limit {
+            RateLimit::Debounce(ms) => {
+                self.pending_msg.replace(Some(msg));
+                let (pending_msg, mailbox) = (Rc::clone(&self.pending_msg), mailbox.clone());
+                // The replaced timer is aborted on drop.
+                self.timer = Some(CmdManager::perform_cmd_with_handle(async move {
+                    TimeoutFuture::new(ms).await;
+                    mailbox.send(pending_msg.take());
+                }));
+                None
+            }
+            RateLimit::Throttle(_) if self.throttled.get() => {
+                self.pending_msg.replace(Some(msg));
+                None
+            }
+            RateLimit::Throttle(ms) => {
+                self.throttled.set(true);
+                let (pending_msg, throttled, mailbox) = (
+                    Rc::clone(&self.pending_msg),
+                    Rc::clone(&self.throttled),
+                    mailbox.clone(),
+                );
+                self.timer = Some(CmdManager::perform_cmd_with_handle(async move {
+                    loop {
+                        TimeoutFuture::new(ms).await;
+                        // Bind the message to release the borrow before `send`.
+                        let msg = pending_msg.take();
+                        match msg {
+                            Some(msg) => mailbox.send(Some(msg)),
+                            None => break,
+                        }
+                    }
+                    throttled.set(false);
+                }));
+                Some(msg)
+            }
+        }
+    }
+}
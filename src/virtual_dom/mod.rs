@@ -1,5 +1,7 @@
 pub mod attrs;
 pub mod el_ref;
+#[cfg(panic = "unwind")]
+pub mod error_boundary;
 pub mod event_handler_manager;
 pub mod mailbox;
 pub mod node;
@@ -12,7 +14,9 @@ pub mod view;
 
 pub use attrs::Attrs;
 pub use el_ref::{el_ref, ElRef, SharedNodeWs};
-pub use event_handler_manager::{EventHandler, EventHandlerManager, Listener};
+#[cfg(panic = "unwind")]
+pub use error_boundary::error_boundary;
+pub use event_handler_manager::{EventHandler, EventHandlerManager, Listener, RateLimit};
 pub use mailbox::Mailbox;
 pub use node::{el_key, on_insert, El, ElKey, InsertEventHandler, IntoNodes, Node, Text};
 pub use style::Style;
@@ -756,6 +760,50 @@ pub mod tests {
         }
     }
 
+    #[wasm_bindgen_test]
+    fn hydrate_prerendered_nodes() {
+        let mailbox = Mailbox::new(|_msg: Option<Msg>| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        parent.set_inner_html(
+            "\n  <!-- prerendered --><ul class=\"a\" title=\"old\"><li>firstsecond</li></ul>\n",
+        );
+        let ul = parent.first_element_child().unwrap();
+        let li = ul.first_element_child().unwrap();
+
+        let mut vdom: Vec<Node<Msg>> = vec![ul![C!["a"], li!["first", "second"], li!["third"]]];
+        patch::hydrate(&doc, &mailbox, &parent, vdom.iter_mut());
+
+        assert_eq!(
+            parent.inner_html(),
+            r#"<ul class="a"><li>firstsecond</li><li>third</li></ul>"#
+        );
+        assert!(ul.is_same_node(parent.first_child().as_ref()));
+        assert!(li.is_same_node(ul.first_child().as_ref()));
+        // Merged text node has been split again.
+        assert_eq!(li.child_nodes().length(), 2);
+        if let Node::Element(ul_el) = &vdom[0] {
+            assert!(ul.is_same_node(ul_el.node_ws.as_ref()));
+        } else {
+            panic!("Node not Element")
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn hydrate_mismatched_nodes() {
+        let mailbox = Mailbox::new(|_msg: Option<Msg>| {});
+
+        let doc = util::document();
+        let parent = doc.create_element("div").unwrap();
+        parent.set_inner_html("<span>old</span><p>extra</p>");
+
+        let mut vdom: Vec<Node<Msg>> = vec![div!["new"]];
+        patch::hydrate(&doc, &mailbox, &parent, vdom.iter_mut());
+
+        assert_eq!(parent.inner_html(), "<div>new</div>");
+    }
+
     /// Tests an update() function that repeatedly sends messages or performs commands.
     #[wasm_bindgen_test(async)]
     #[allow(clippy::items_after_statements)]
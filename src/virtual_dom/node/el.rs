@@ -3,7 +3,7 @@ use super::super::{
     Tag, Text,
 };
 use crate::{
-    app::MessageMapper,
+    app::{IntoOptionMsg, MessageMapper},
     browser::{
         dom::{virtual_dom_bridge, Namespace},
         util,
@@ -311,19 +311,9 @@ impl<Ms> fmt::Debug for InsertEventHandler<Ms> {
 /// Attaches an event handler that will trigger the given `Msg` when the element
 /// is inserted into the DOM.
 ///
-/// Handler has to return `Msg`, `Option<Msg>` or `()`.
-///
-/// # Panics
-///
-/// Panics when the handler doesn't return `Msg` or `()`. (It will be changed to a compile-time error).
-pub fn on_insert<Ms: 'static, MsU: 'static>(
+/// Handler has to return `Msg`, `Option<Msg>` or `()` (see `IntoOptionMsg`).
+pub fn on_insert<Ms: 'static, MsU: IntoOptionMsg<Ms, Marker> + 'static, Marker>(
     handler: impl FnOnce(web_sys::Element) -> MsU + 'static + Clone,
 ) -> InsertEventHandler<Ms> {
-    let handler = map_callback_return_to_option_ms!(
-        dyn Fn(web_sys::Element) -> Option<Ms>,
-        handler.clone(),
-        "Handler can return only Msg, Option<Msg> or ()!",
-        Rc
-    );
-    InsertEventHandler(handler)
+    InsertEventHandler(Rc::new(move |el| handler.clone()(el).into_option_msg()))
 }
@@ -0,0 +1,210 @@
+//! Hydration - the first render adopts the existing (e.g. prerendered) DOM nodes
+//! instead of recreating them.
+
+use super::super::{El, Mailbox, Node, Text};
+use crate::browser::dom::{virtual_dom_bridge, Namespace};
+use wasm_bindgen::JsCast;
+use web_sys::Document;
+
+/// Walk children of `mount_point` alongside the new vdom, assign existing DOM nodes
+/// to the matching vdom nodes and patch only the differences.
+///
+/// Mismatched nodes are replaced, missing ones are created and superfluous ones are removed.
+/// Mismatches are reported to the console in debug builds.
+pub(crate) fn hydrate<'a, Ms, NI>(
+    document: &Document,
+    mailbox: &Mailbox<Ms>,
+    mount_point: &web_sys::Node,
+    new_children_iter: NI,
+) where
+    Ms: 'static,
+    NI: Iterator<Item = &'a mut Node<Ms>>,
+{
+    let hydration = Hydration {
+        document,
+        mailbox,
+        // Don't report "missing" nodes when there is nothing to hydrate.
+        report_mismatches: cfg!(debug_assertions) && has_significant_content(mount_point),
+    };
+    hydration.hydrate_children(mount_point, new_children_iter);
+}
+
+struct Hydration<'a, Ms: 'static> {
+    document: &'a Document,
+    mailbox: &'a Mailbox<Ms>,
+    report_mismatches: bool,
+}
+
+impl<Ms: 'static> Hydration<'_, Ms> {
+    fn hydrate_children<'n>(
+        &self,
+        parent: &web_sys::Node,
+        new_children_iter: impl Iterator<Item = &'n mut Node<Ms>>,
+    ) {
+        let mut next_ws = parent.first_child();
+        for new in new_children_iter {
+            match new {
+                Node::Element(el) => self.hydrate_el(el, parent, &mut next_ws),
+                Node::Text(text) => self.hydrate_text(text, parent, &mut next_ws),
+                Node::Empty => (),
+                // There is no old vdom to take the node from.
+                Node::NoChange => *new = Node::Empty,
+            }
+        }
+        while let Some(node_ws) = next_ws {
+            next_ws = node_ws.next_sibling();
+            if is_significant(&node_ws) {
+                self.report(|| format!("superfluous node {}", node_ws.node_name()));
+            }
+            virtual_dom_bridge::remove_node(&node_ws, parent);
+        }
+    }
+
+    fn hydrate_el(
+        &self,
+        el: &mut El<Ms>,
+        parent: &web_sys::Node,
+        next_ws: &mut Option<web_sys::Node>,
+    ) {
+        skip_insignificant(parent, next_ws, true);
+
+        let Some(node_ws) = next_ws.take() else {
+            self.report(|| format!("missing element <{}>", el.tag));
+            virtual_dom_bridge::assign_ws_nodes_to_el(self.document, el);
+            virtual_dom_bridge::insert_el_and_children(el, parent, None, self.mailbox);
+            return;
+        };
+        *next_ws = node_ws.next_sibling();
+
+        if !el_matches(el, &node_ws) {
+            self.report(|| format!("expected <{}>, found {}", el.tag, node_ws.node_name()));
+            virtual_dom_bridge::assign_ws_nodes_to_el(self.document, el);
+            virtual_dom_bridge::insert_el_and_children(
+                el,
+                parent,
+                Some(node_ws.clone()),
+                self.mailbox,
+            );
+            virtual_dom_bridge::remove_node(&node_ws, parent);
+            return;
+        }
+
+        let changed_attrs = virtual_dom_bridge::hydrate_el_details(el, &node_ws);
+        if !changed_attrs.is_empty() {
+            self.report(|| {
+                format!(
+                    "attributes [{}] of <{}> differ",
+                    changed_attrs.join(", "),
+                    el.tag
+                )
+            });
+        }
+        self.hydrate_children(&node_ws, el.children.iter_mut());
+        el.node_ws = Some(node_ws);
+        virtual_dom_bridge::wire_up_el(el, self.mailbox);
+    }
+
+    fn hydrate_text(
+        &self,
+        text: &mut Text,
+        parent: &web_sys::Node,
+        next_ws: &mut Option<web_sys::Node>,
+    ) {
+        skip_insignificant(parent, next_ws, false);
+
+        let node_ws = match next_ws.take() {
+            Some(node_ws) if node_ws.node_type() == web_sys::Node::TEXT_NODE => node_ws,
+            next => {
+                // Empty texts aren't rendered at all.
+                if !text.text.is_empty() {
+                    self.report(|| format!("missing text {:?}", text.text));
+                }
+                virtual_dom_bridge::assign_ws_nodes_to_text(self.document, text);
+                let new_ws = text.node_ws.as_ref().expect("Missing websys node for Text");
+                virtual_dom_bridge::insert_node(new_ws, parent, next.clone());
+                *next_ws = next;
+                return;
+            }
+        };
+
+        let content = node_ws.text_content().unwrap_or_default();
+        if content == text.text {
+            *next_ws = node_ws.next_sibling();
+        } else if !text.text.is_empty() && content.starts_with(text.text.as_ref()) {
+            // Adjacent text nodes are merged when the markup is parsed - split them again.
+            let offset =
+                u32::try_from(text.text.encode_utf16().count()).expect("Text node is too long");
+            let rest = node_ws
+                .unchecked_ref::<web_sys::Text>()
+                .split_text(offset)
+                .expect("Problem splitting text node");
+            *next_ws = Some(rest.into());
+        } else if text.text.is_empty() {
+            virtual_dom_bridge::assign_ws_nodes_to_text(self.document, text);
+            let new_ws = text.node_ws.as_ref().expect("Missing websys node for Text");
+            virtual_dom_bridge::insert_node(new_ws, parent, Some(node_ws.clone()));
+            *next_ws = Some(node_ws);
+            return;
+        } else {
+            self.report(|| format!("expected text {:?}, found {:?}", text.text, content));
+            node_ws.set_text_content(Some(&text.text));
+            *next_ws = node_ws.next_sibling();
+        }
+        text.node_ws = Some(node_ws);
+    }
+
+    fn report(&self, message: impl FnOnce() -> String) {
+        if self.report_mismatches {
+            web_sys::console::warn_1(&format!("Hydration mismatch: {}", message()).into());
+        }
+    }
+}
+
+fn el_matches<Ms>(el: &El<Ms>, node_ws: &web_sys::Node) -> bool {
+    let Some(element) = node_ws.dyn_ref::<web_sys::Element>() else {
+        return false;
+    };
+    let namespace = el.namespace.as_ref().unwrap_or(&Namespace::Html);
+    element.namespace_uri().as_deref() == Some(namespace.as_str())
+        && element.local_name().eq_ignore_ascii_case(el.tag.as_str())
+}
+
+/// Comments and whitespace-only texts (e.g. formatting in `index.html`) aren't represented in the vdom.
+fn is_significant(node_ws: &web_sys::Node) -> bool {
+    match node_ws.node_type() {
+        web_sys::Node::COMMENT_NODE => false,
+        web_sys::Node::TEXT_NODE => node_ws
+            .text_content()
+            .map_or(false, |text| !text.trim().is_empty()),
+        _ => true,
+    }
+}
+
+fn has_significant_content(node_ws: &web_sys::Node) -> bool {
+    let children = node_ws.child_nodes();
+    (0..children.length())
+        .filter_map(|index| children.item(index))
+        .any(|child| is_significant(&child))
+}
+
+/// Remove comments (and whitespace-only texts if `skip_whitespace` is `true`)
+/// in front of the next node.
+fn skip_insignificant(
+    parent: &web_sys::Node,
+    next_ws: &mut Option<web_sys::Node>,
+    skip_whitespace: bool,
+) {
+    while let Some(node_ws) = next_ws.as_ref() {
+        let removable = match node_ws.node_type() {
+            web_sys::Node::COMMENT_NODE => true,
+            web_sys::Node::TEXT_NODE => skip_whitespace && !is_significant(node_ws),
+            _ => false,
+        };
+        if !removable {
+            return;
+        }
+        let next_sibling = node_ws.next_sibling();
+        virtual_dom_bridge::remove_node(node_ws, parent);
+        *next_ws = next_sibling;
+    }
+}
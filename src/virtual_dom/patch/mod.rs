@@ -6,7 +6,9 @@ use crate::app::App;
 use crate::browser::dom::virtual_dom_bridge;
 use web_sys::Document;
 
+mod hydrate;
 mod patch_gen;
+pub(crate) use hydrate::hydrate;
 use patch_gen::{PatchCommand, PatchGen};
 
 // We assume that when we run this, the new vdom doesn't have assigned `web_sys::Node`s -
@@ -218,13 +218,13 @@ mod tests {
 
     #[wasm_bindgen_test]
     fn update_el_event_handler() {
-        let event_handler: EventHandler<Ms> = ev(Ev::Click, |_| ());
+        let event_handler: EventHandler<Ms> = ev(Ev::Click, |_| None);
         let _el: Node<Ms> = div![event_handler];
     }
 
     #[wasm_bindgen_test]
     fn update_el_ref_event_handler() {
-        let event_handler: &EventHandler<Ms> = &ev(Ev::Click, |_| ());
+        let event_handler: &EventHandler<Ms> = &ev(Ev::Click, |_| None);
         let _el: Node<Ms> = div![event_handler];
     }
 